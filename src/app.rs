@@ -1,6 +1,11 @@
-use crate::board::{Board, BoardColumn, Project, Task};
+use crate::board::{Board, ChecklistItem, ColumnSort, InsertPosition, Project, Task};
+use crate::dates;
+use crate::merge;
+use crate::repo_board;
 use crate::storage;
 use crate::theme::Theme;
+use crate::todo_txt;
+use std::path::{Path, PathBuf};
 
 // application state
 pub struct App {
@@ -15,61 +20,155 @@ pub struct App {
     pub input_mode: InputMode,
     pub input_buffer: String,
     pub focused_field: TaskField,
-    pub disable_saving: bool, // For testing
+    pub selected_checklist_index: usize, // for checklist in task detail view
+    pub selected_link_index: usize,      // for dependencies in task detail view
+    pub pending_move: Option<usize>,     // target column of a move waiting for WIP confirmation
+    pub insert_position: InsertPosition, // where moved tasks land in the target column
+    pub disable_saving: bool,            // For testing
+    pub saving_blocked: bool,            // the projects couldn't be loaded, don't overwrite them
+    pub newer_file: bool, // the projects were written by a newer tui-kanban, never overwrite them
+    pub unsaved_changes: bool, // in-memory state differs from what's on disk
+    pub read_only: bool,  // another instance holds the data dir lock
+    data_lock: Option<storage::DataLock>,
+    lock_path: PathBuf, // lock file of the data dir
+    backend: Box<dyn storage::Backend>,
+    pub repo_project: Option<String>, // id of the board from .tui-kanban/board.json
+    todo_txt: Vec<storage::TodoTxtSync>, // projects synced with todo.txt files
+    todo_txt_dir: PathBuf,            // the todo.txt files as last synced
+    todo_txt_merged: Vec<todo_txt::Merged>, // merged into the projects, written back once saved
+    file_stamp: Option<storage::FileStamp>, // stored data as we last read or wrote it
+    dismissed_stamp: Option<storage::FileStamp>, // changes on disk we chose not to reload
     pub theme: Theme,
     pub selected_theme_index: usize, // for theme selector view
     pub error_message: String,
+    pub undo_stack: Vec<HistoryEntry>,
+    pub redo_stack: Vec<HistoryEntry>,
+    saved_projects: Vec<Project>, // state as of the last save, what undo goes back to
+    pub status_message: Option<String>, // shown in the footer, e.g. the undone action
+}
+
+// how many actions can be undone
+const HISTORY_LIMIT: usize = 100;
+
+// board state before (undo) or after (redo) an action
+pub struct HistoryEntry {
+    pub action: String,
+    projects: Vec<Project>,
+    current_project: usize,
+    selected_column: usize,
+    selected_index: usize,
+}
+
+// apply what changed from `before` to `after` to a snapshot of the projects,
+// merging card by card so the snapshot's own differences survive
+fn fold_changes(before: &[Project], after: &[Project], snapshot: &mut [Project]) {
+    for project in snapshot.iter_mut() {
+        let base = before.iter().find(|p| p.id == project.id);
+        let theirs = after.iter().find(|p| p.id == project.id);
+        if let (Some(base), Some(theirs)) = (base, theirs) {
+            if serde_json::to_value(base).ok() != serde_json::to_value(theirs).ok() {
+                *project = merge::merge_projects(base, project, theirs).project;
+            }
+        }
+    }
 }
 
 // which field is focused in task detail view
-#[derive(PartialEq, Clone, Copy)]
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum TaskField {
     Title,
     Tags,
+    DueDate,
     Description,
+    Checklist,
+    Dependencies,
+}
+
+// direction of a dependency link shown in task detail view
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum LinkKind {
+    BlockedBy,
+    Blocks,
 }
 
 // input mode
-#[derive(PartialEq)]
+#[derive(PartialEq, Debug)]
 pub enum InputMode {
     Normal,
     AddingTask,
     AddingTag,
     ViewingTask,
     EditingTitle,
+    EditingDueDate,
     EditingDescription,
+    AddingChecklistItem,
+    AddingBlocker,
     ViewingHelp,
     ProjectList,
     AddingProject,
     AddingColumn,
     RenamingColumn,
+    SettingWipLimit,
     ConfirmingDelete,
+    ConfirmingWipLimit,
+    ConfirmingReload,
     SelectingTheme,
     ShowErrorInfo,
 }
 
 impl InputMode {
     pub fn has_open_input(&self) -> bool {
-        match self {
+        matches!(
+            self,
             Self::AddingTask
-            | Self::AddingTag
-            | Self::EditingTitle
-            | Self::EditingDescription
-            | Self::RenamingColumn
-            | Self::AddingProject => true,
-            _ => false,
-        }
+                | Self::AddingTag
+                | Self::EditingTitle
+                | Self::EditingDueDate
+                | Self::EditingDescription
+                | Self::AddingChecklistItem
+                | Self::AddingBlocker
+                | Self::RenamingColumn
+                | Self::SettingWipLimit
+                | Self::AddingProject
+        )
     }
 }
 
 impl App {
     // create new app state
     pub fn new() -> Self {
-        let projects = storage::load_projects();
+        // Only one instance may write; the others follow along read-only
+        let lock_path = storage::get_lock_path();
+        let (data_lock, lock_warning) = match storage::try_lock(&lock_path) {
+            Ok(Some(lock)) => (Some(lock), None),
+            Ok(None) => (None, Some(Self::read_only_warning(&lock_path))),
+            // Can't create the lock file at all, e.g. read-only config dir: run unlocked
+            Err(_) => (None, None),
+        };
+        let read_only = data_lock.is_none() && lock_warning.is_some();
+
+        // Start with an empty board if the stored projects are corrupt, but don't save over them
         let config = storage::load_config();
+        let repo_board::Opened {
+            backend,
+            projects: load_result,
+            repo_project,
+        } = repo_board::open_projects(&config, read_only);
+        let (projects, load_error) = match load_result {
+            Ok(projects) => (projects, None),
+            Err(e) => (vec![Project::new("Default".to_string())], Some(e)),
+        };
+        // A file moved aside on an earlier start still needs a decision
+        let pending_corrupt = match &load_error {
+            None => backend.pending_corrupt(),
+            Some(_) => Vec::new(),
+        };
+        let file_stamp = backend.stamp();
+        let saved_projects = projects.clone();
 
         // Determine which project to start with
-        let current_project = Self::determine_initial_project(&projects, &config);
+        let current_project =
+            Self::determine_initial_project(&projects, &config, repo_project.as_deref());
 
         // Load theme from config
         let theme = config
@@ -78,7 +177,7 @@ impl App {
             .and_then(|name| Theme::from_name(name))
             .unwrap_or_default();
 
-        Self {
+        let mut app = Self {
             projects,
             current_project,
             selected_project_index: current_project,
@@ -90,37 +189,113 @@ impl App {
             input_mode: InputMode::Normal,
             input_buffer: String::new(),
             focused_field: TaskField::Title,
+            selected_checklist_index: 0,
+            selected_link_index: 0,
+            pending_move: None,
+            insert_position: config.insert_position,
             disable_saving: false,
+            saving_blocked: false,
+            newer_file: false,
+            unsaved_changes: false,
+            read_only,
+            data_lock,
+            lock_path,
+            backend,
+            repo_project,
+            todo_txt: config.todo_txt.clone(),
+            todo_txt_dir: storage::get_todo_txt_dir(),
+            todo_txt_merged: Vec::new(),
+            file_stamp,
+            dismissed_stamp: None,
             theme,
             selected_theme_index: 0,
             error_message: String::new(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            saved_projects,
+            status_message: None,
+        };
+
+        if let Some(storage::LoadError::Newer(e)) = load_error {
+            app.newer_file = true;
+            app.handle_error(format!(
+                "{}\nChanges are not being saved, and the file is left as it is.",
+                e
+            ));
+        } else if let Some(e) = load_error {
+            app.saving_blocked = true;
+            app.handle_error(format!(
+                "{}\nChanges are not being saved. Press Ctrl+s to save anyway, or quit and fix the problem (a JSON board can be rolled back with tui-kanban --restore-backup).",
+                e
+            ));
+        } else if !pending_corrupt.is_empty() {
+            app.saving_blocked = true;
+            let files: Vec<String> = pending_corrupt
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            app.handle_error(format!(
+                "Projects were set aside as corrupt and not dealt with yet:\n{}\nChanges are not being saved. Fix the file and move it back, restore a backup with tui-kanban --restore-backup, or press Ctrl+s to save this board anyway.",
+                files.join("\n")
+            ));
+        } else if let Some(warning) = lock_warning {
+            app.handle_error(warning);
+        } else if app.sync_todo_txt() {
+            // Bring in what changed in the todo.txt files while we were closed
+            app.write_projects();
+        } else {
+            // Nothing to save, but a missing file is written again
+            app.commit_todo_txt();
         }
+        app
+    }
+
+    fn read_only_warning(lock_path: &Path) -> String {
+        let holder = storage::lock_holder_pid(lock_path)
+            .map(|pid| format!(" (pid {})", pid))
+            .unwrap_or_default();
+        format!(
+            "Another tui-kanban instance{} has this board open, so this one is read-only.\nIt follows changes made there, but edits made here are not saved. Press Ctrl+s to save once the other instance has closed.",
+            holder
+        )
     }
 
     // Determine which project to start with based on priority:
-    // 1. Directory-specific .tui-kanban-project file
-    // 2. Global default from config.json
-    // 3. First project (index 0)
-    fn determine_initial_project(projects: &[Project], config: &storage::Config) -> usize {
-        // Priority 1: Check for directory-specific project file
+    // 1. Board committed to the repository (.tui-kanban/board.json)
+    // 2. Directory-specific .tui-kanban-project file
+    // 3. Global default from config.json
+    // 4. First project (index 0)
+    pub fn determine_initial_project(
+        projects: &[Project],
+        config: &storage::Config,
+        repo_project: Option<&str>,
+    ) -> usize {
+        // Priority 1: Check for a repository board
+        if let Some(index) = repo_project.and_then(|id| projects.iter().position(|p| p.id == id)) {
+            return index;
+        }
+
+        // Priority 2: Check for directory-specific project file
         if let Some(dir_project_name) = storage::get_directory_project() {
             if let Some(index) = projects.iter().position(|p| p.name == dir_project_name) {
                 return index;
             }
         }
 
-        // Priority 2: Check config for default project
+        // Priority 3: Check config for default project
         if let Some(default_name) = &config.default_project {
             if let Some(index) = projects.iter().position(|p| p.name == *default_name) {
                 return index;
             }
         }
 
-        // Priority 3: Default to first project
+        // Priority 4: Default to first project
         0
     }
 
+    #[cfg(test)]
     pub fn new_with_projects(projects: Vec<Project>) -> Self {
+        let saved_projects = projects.clone();
         Self {
             projects,
             current_project: 0,
@@ -133,10 +308,31 @@ impl App {
             input_mode: InputMode::Normal,
             input_buffer: String::new(),
             focused_field: TaskField::Title,
+            selected_checklist_index: 0,
+            selected_link_index: 0,
+            pending_move: None,
+            insert_position: InsertPosition::Bottom,
             disable_saving: true,
+            saving_blocked: false,
+            newer_file: false,
+            unsaved_changes: false,
+            read_only: false,
+            data_lock: None,
+            lock_path: PathBuf::new(), // never locked, saving is disabled
+            backend: Box::new(storage::JsonBackend::new("projects".into())), // never written, saving is disabled
+            repo_project: None,
+            todo_txt: Vec::new(),
+            todo_txt_dir: PathBuf::from("todo-txt"),
+            todo_txt_merged: Vec::new(),
+            file_stamp: None,
+            dismissed_stamp: None,
             theme: Theme::default(),
             selected_theme_index: 0,
             error_message: String::new(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            saved_projects,
+            status_message: None,
         }
     }
 
@@ -155,12 +351,329 @@ impl App {
         &self.projects[self.current_project].name
     }
 
-    // save current state
-    fn save(&self) {
+    // get id of the selected task, if any
+    pub fn selected_task_id(&self) -> Option<String> {
+        self.board()
+            .get_column(self.selected_column)
+            .and_then(|col| col.tasks.get(self.selected_index))
+            .map(|task| task.id.clone())
+    }
+
+    // get the selected task mutably, addressed by its id
+    fn selected_task_mut(&mut self) -> Option<&mut Task> {
+        let id = self.selected_task_id()?;
+        self.board_mut().get_task_mut(&id)
+    }
+
+    // move the selection to the task with the given id, returns false if not found
+    pub fn select_task(&mut self, id: &str) -> bool {
+        match self.board().find_task(id) {
+            Some((col_idx, task_idx)) => {
+                self.selected_column = col_idx;
+                self.selected_index = task_idx;
+                self.update_scroll();
+                true
+            }
+            None => false,
+        }
+    }
+
+    // record `action` in the undo history and save current state
+    fn save(&mut self, action: &str) {
+        let previous = std::mem::replace(&mut self.saved_projects, self.projects.clone());
+        let entry = self.history_entry(action.to_string(), previous);
+        self.undo_stack.push(entry);
+        if self.undo_stack.len() > HISTORY_LIMIT {
+            self.undo_stack.remove(0);
+        }
+        self.redo_stack.clear();
+        self.status_message = None;
+        self.write_projects();
+    }
+
+    fn write_projects(&mut self) {
         if self.disable_saving {
             return;
         }
-        let _ = storage::save_projects(&self.projects);
+        // Don't write over a file we couldn't load, or over someone else's changes;
+        // check_for_external_changes asks what to do
+        if self.saving_blocked
+            || self.newer_file
+            || self.read_only
+            || self.backend.stamp() != self.file_stamp
+        {
+            self.unsaved_changes = true;
+            return;
+        }
+        self.sync_todo_txt();
+        match self.backend.save(&self.projects) {
+            Ok(()) => {
+                self.unsaved_changes = false;
+                self.file_stamp = self.backend.stamp();
+                self.commit_todo_txt();
+            }
+            Err(e) => {
+                self.unsaved_changes = true;
+                self.status_message = Some(format!("Save failed: {}", e));
+            }
+        }
+    }
+
+    // reconcile the projects with their todo.txt files; returns whether that
+    // changed the projects
+    fn sync_todo_txt(&mut self) -> bool {
+        if self.todo_txt.is_empty() || self.disable_saving {
+            return false;
+        }
+        let before = self.projects.clone();
+        let synced = todo_txt::merge_all(
+            &mut self.projects,
+            &mut self.todo_txt,
+            &self.todo_txt_dir,
+            &self.todo_txt_merged,
+        );
+        self.todo_txt_merged = synced.merged;
+        if synced.changed {
+            // The file's changes aren't an undoable action: fold them into
+            // every snapshot so undo and redo keep them
+            fold_changes(&before, &self.projects, &mut self.saved_projects);
+            for entry in self.undo_stack.iter_mut().chain(self.redo_stack.iter_mut()) {
+                fold_changes(&before, &self.projects, &mut entry.projects);
+            }
+            self.clamp_selection();
+            self.status_message = Some("Merged changes from todo.txt".to_string());
+        }
+        if !synced.warnings.is_empty() {
+            self.status_message = Some(synced.warnings.join("; "));
+        }
+        // Entries now name their projects by id
+        if synced.config_changed {
+            let mut config = storage::load_config();
+            config.todo_txt = self.todo_txt.clone();
+            let _ = storage::save_config(&config);
+        }
+        synced.changed
+    }
+
+    // write the saved projects back to the todo.txt files merged into them
+    fn commit_todo_txt(&mut self) {
+        let warnings = todo_txt::commit_all(&self.projects, &self.todo_txt_merged);
+        self.todo_txt_merged.clear();
+        if !warnings.is_empty() {
+            self.status_message = Some(warnings.join("; "));
+        }
+    }
+
+    // save now, lifting the block after a failed load and overwriting any
+    // changes made on disk by someone else
+    pub fn force_save(&mut self) {
+        if self.newer_file {
+            self.status_message =
+                Some("Not saved: the projects were written by a newer tui-kanban".to_string());
+            return;
+        }
+        if self.read_only && !self.take_data_lock() {
+            self.status_message =
+                Some("Read-only: another instance still has the board open".to_string());
+            return;
+        }
+        let was_blocked = self.saving_blocked;
+        self.saving_blocked = false;
+        self.file_stamp = self.backend.stamp();
+        self.write_projects();
+        if was_blocked {
+            // Saving anyway is the decision about the files moved aside
+            if !self.unsaved_changes {
+                self.backend.resolve_corrupt();
+            }
+            self.status_message = Some("Saving enabled".to_string());
+        }
+    }
+
+    // try to become the writing instance, e.g. after the other one closed
+    fn take_data_lock(&mut self) -> bool {
+        if let Ok(Some(lock)) = storage::try_lock(&self.lock_path) {
+            self.data_lock = Some(lock);
+            self.read_only = false;
+        }
+        !self.read_only
+    }
+
+    // reload the projects if they changed on disk, or ask first if we have
+    // changes of our own that were never written
+    pub fn check_for_external_changes(&mut self) {
+        // Wait until the user isn't in the middle of something
+        if self.disable_saving || self.input_mode != InputMode::Normal {
+            return;
+        }
+        let stamp = self.backend.stamp();
+        if stamp == self.file_stamp {
+            return;
+        }
+        // Already asked about this version and left it for later
+        if stamp.is_some() && stamp == self.dismissed_stamp {
+            return;
+        }
+        // Deleted: nothing to reload, write ours back on the next save
+        if stamp.is_none() {
+            self.file_stamp = None;
+            return;
+        }
+
+        if self.unsaved_changes {
+            self.input_mode = InputMode::ConfirmingReload;
+        } else {
+            self.reload_from_disk();
+        }
+    }
+
+    // replace in-memory projects with what is on disk, dropping local changes
+    pub fn reload_from_disk(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let stamp = self.backend.stamp();
+        match self.backend.reload() {
+            Ok(projects) => {
+                self.file_stamp = stamp;
+                self.saving_blocked = false;
+                self.newer_file = false;
+                self.unsaved_changes = false;
+                // Merge the todo.txt files into these from their last written state
+                self.todo_txt_merged.clear();
+                self.replace_projects(projects);
+                self.status_message = Some("Reloaded projects (changed on disk)".to_string());
+            }
+            Err(storage::LoadError::Newer(e)) => {
+                self.file_stamp = stamp;
+                self.newer_file = true;
+                self.handle_error(format!("{}\nChanges are not being saved.", e));
+            }
+            Err(e) => {
+                // Remember the stamp so we don't ask again until it changes again
+                self.file_stamp = stamp;
+                self.saving_blocked = true;
+                self.handle_error(format!(
+                    "{}\nChanges are not being saved. Press Ctrl+s to overwrite it with this board.",
+                    e
+                ));
+            }
+        }
+    }
+
+    // keep local state and write it over the changes on disk
+    pub fn keep_local_changes(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.force_save();
+        if self.read_only {
+            // Couldn't write, don't ask again until the files change once more
+            self.dismissed_stamp = self.backend.stamp();
+        } else {
+            self.status_message =
+                Some("Kept local changes, overwrote the files on disk".to_string());
+        }
+    }
+
+    // close the prompt without deciding; local changes stay unsaved until
+    // Ctrl+s overwrites the files or they change on disk again
+    pub fn dismiss_reload(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.dismissed_stamp = self.backend.stamp();
+        self.status_message =
+            Some("Not saved: the files changed on disk. Ctrl+s overwrites them".to_string());
+    }
+
+    // swap in projects loaded from disk, keeping the selection where possible
+    fn replace_projects(&mut self, projects: Vec<Project>) {
+        let project_id = self.projects[self.current_project].id.clone();
+        let column_id = self
+            .board()
+            .get_column(self.selected_column)
+            .map(|column| column.id.clone());
+        let task_id = self.selected_task_id();
+
+        self.projects = if projects.is_empty() {
+            vec![Project::new("Default".to_string())]
+        } else {
+            projects
+        };
+        self.saved_projects = self.projects.clone();
+        // Undoing to a state from before the reload would silently revert it
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+
+        self.current_project = self
+            .projects
+            .iter()
+            .position(|project| project.id == project_id)
+            .unwrap_or(0);
+        self.selected_project_index = self.current_project;
+
+        if task_id.is_some_and(|id| self.select_task(&id)) {
+            return;
+        }
+        if let Some(column_idx) = column_id.and_then(|id| {
+            self.board()
+                .columns
+                .iter()
+                .position(|column| column.id == id)
+        }) {
+            self.selected_column = column_idx;
+        }
+        self.clamp_selection();
+        self.update_scroll();
+    }
+
+    fn history_entry(&self, action: String, projects: Vec<Project>) -> HistoryEntry {
+        HistoryEntry {
+            action,
+            projects,
+            current_project: self.current_project,
+            selected_column: self.selected_column,
+            selected_index: self.selected_index,
+        }
+    }
+
+    // undo the last action
+    pub fn undo(&mut self) {
+        match self.undo_stack.pop() {
+            Some(entry) => {
+                let redo = self.restore(entry);
+                self.status_message = Some(format!("Undid: {}", redo.action));
+                self.redo_stack.push(redo);
+                self.write_projects();
+            }
+            None => self.status_message = Some("Nothing to undo".to_string()),
+        }
+    }
+
+    // redo the last undone action
+    pub fn redo(&mut self) {
+        match self.redo_stack.pop() {
+            Some(entry) => {
+                let undo = self.restore(entry);
+                self.status_message = Some(format!("Redid: {}", undo.action));
+                self.undo_stack.push(undo);
+                self.write_projects();
+            }
+            None => self.status_message = Some("Nothing to redo".to_string()),
+        }
+    }
+
+    // swap in the state of a history entry, returns the state it replaced;
+    // the caller saves once the history is in order
+    fn restore(&mut self, entry: HistoryEntry) -> HistoryEntry {
+        let projects = std::mem::replace(&mut self.projects, entry.projects);
+        let replaced = self.history_entry(entry.action, projects);
+
+        self.saved_projects = self.projects.clone();
+        self.current_project = entry
+            .current_project
+            .min(self.projects.len().saturating_sub(1));
+        self.selected_project_index = self.current_project;
+        self.selected_column = entry.selected_column;
+        self.selected_index = entry.selected_index;
+        self.clamp_selection();
+        self.update_scroll();
+        replaced
     }
 
     // move selection up
@@ -221,11 +734,7 @@ impl App {
             .board()
             .get_column(self.selected_column)
             .map_or(0, |col| col.tasks.len());
-        let max_scroll = if column_len > self.visible_items {
-            column_len - self.visible_items
-        } else {
-            0
-        };
+        let max_scroll = column_len.saturating_sub(self.visible_items);
 
         // scroll down if selected is below visible area
         if self.selected_index >= self.scroll_offset + self.visible_items {
@@ -245,54 +754,153 @@ impl App {
 
     // move selected task to next column
     pub fn move_task_forward(&mut self) {
+        let next_column_idx = self.selected_column + 1;
+        if next_column_idx < self.board().columns.len() {
+            self.move_selected_task(next_column_idx, false);
+        }
+    }
+
+    // move selected task to previous column
+    pub fn move_task_backward(&mut self) {
+        if self.selected_column > 0 {
+            self.move_selected_task(self.selected_column - 1, false);
+        }
+    }
+
+    // finish a move that was waiting for WIP limit confirmation
+    pub fn confirm_pending_move(&mut self) {
+        self.input_mode = InputMode::Normal;
+        if let Some(target_column_idx) = self.pending_move.take() {
+            self.move_selected_task(target_column_idx, true);
+        }
+    }
+
+    pub fn cancel_pending_move(&mut self) {
+        self.pending_move = None;
+        self.input_mode = InputMode::Normal;
+    }
+
+    // move selected task to another column; `confirmed` skips the WIP limit prompt
+    fn move_selected_task(&mut self, target_column_idx: usize, confirmed: bool) {
         let current_column_idx = self.selected_column;
-        let next_column_idx = current_column_idx + 1;
+        let selected_idx = self.selected_index; // Capture before mutable borrow
+        if selected_idx >= self.board().columns[current_column_idx].tasks.len() {
+            return; // No task to move
+        }
 
-        if next_column_idx < self.board().columns.len() {
-            let selected_idx = self.selected_index; // Capture before mutable borrow
+        // Blocked tasks may not move past the gate column
+        if let Some(message) =
+            self.board()
+                .blocked_move_message(current_column_idx, selected_idx, target_column_idx)
+        {
+            self.handle_error(message);
+            return;
+        }
 
-            // Remove task from current column
-            let task = {
-                let current_column = self.board_mut().get_column_mut(current_column_idx).unwrap();
-                if selected_idx < current_column.tasks.len() {
-                    current_column.tasks.remove(selected_idx)
-                } else {
-                    return; // No task to move
-                }
-            };
+        // Ask before pushing a column over its WIP limit
+        if !confirmed && self.board().columns[target_column_idx].is_at_wip_limit() {
+            self.pending_move = Some(target_column_idx);
+            self.input_mode = InputMode::ConfirmingWipLimit;
+            return;
+        }
 
-            // Add task to next column
-            let next_column = self.board_mut().get_column_mut(next_column_idx).unwrap();
-            next_column.tasks.push(task);
+        // Remove task from current column
+        let mut task = self
+            .board_mut()
+            .get_column_mut(current_column_idx)
+            .unwrap()
+            .tasks
+            .remove(selected_idx);
+
+        // Add task to target column
+        let is_done = self.board().is_done_column(target_column_idx);
+        let insert_position = self.insert_position;
+        let target_column = self.board_mut().get_column_mut(target_column_idx).unwrap();
+        task.enter_column(&target_column.id, is_done);
+        let insert_idx = insert_position.index(target_column.tasks.len(), selected_idx);
+        target_column.tasks.insert(insert_idx, task);
+
+        self.clamp_selection();
+        self.sort_columns();
+        self.save("move task");
+    }
 
-            self.clamp_selection();
-            self.save();
+    // move selected task up within its column, selection follows
+    pub fn move_task_up(&mut self) {
+        if self.selected_index > 0 {
+            self.swap_selected_task(self.selected_index - 1);
         }
     }
 
-    // move selected task to previous column
-    pub fn move_task_backward(&mut self) {
-        let current_column_idx = self.selected_column;
-        if current_column_idx > 0 {
-            let prev_column_idx = current_column_idx - 1;
-            let selected_idx = self.selected_index; // Capture before mutable borrow
-
-            // Remove task from current column
-            let task = {
-                let current_column = self.board_mut().get_column_mut(current_column_idx).unwrap();
-                if selected_idx < current_column.tasks.len() {
-                    current_column.tasks.remove(selected_idx)
-                } else {
-                    return; // No task to move
-                }
-            };
+    // move selected task down within its column, selection follows
+    pub fn move_task_down(&mut self) {
+        self.swap_selected_task(self.selected_index + 1);
+    }
 
-            // Add task to previous column
-            let prev_column = self.board_mut().get_column_mut(prev_column_idx).unwrap();
-            prev_column.tasks.push(task);
+    fn swap_selected_task(&mut self, target_idx: usize) {
+        let col_idx = self.selected_column; // Capture before mutable borrow
+        let selected_idx = self.selected_index;
+        let Some(column) = self.board().get_column(col_idx) else {
+            return;
+        };
+        if selected_idx >= column.tasks.len() || target_idx >= column.tasks.len() {
+            return;
+        }
+        if column.sort != ColumnSort::Manual {
+            self.handle_error(format!(
+                "'{}' is sorted by priority. Press Shift+S to switch it to manual order first.",
+                column.name
+            ));
+            return;
+        }
 
-            self.clamp_selection();
-            self.save();
+        self.board_mut().columns[col_idx]
+            .tasks
+            .swap(selected_idx, target_idx);
+        self.selected_index = target_idx;
+        self.update_scroll();
+        self.save("reorder task");
+    }
+
+    // cycle where moved tasks land in the target column (saved to config)
+    pub fn cycle_insert_position(&mut self) {
+        self.insert_position = self.insert_position.next();
+        if self.disable_saving {
+            return;
+        }
+        let mut config = storage::load_config();
+        config.insert_position = self.insert_position;
+        let _ = storage::save_config(&config);
+    }
+
+    // raise priority of selected task
+    pub fn raise_priority(&mut self) {
+        if let Some(task) = self.selected_task_mut() {
+            task.priority = task.priority.raised();
+            task.touch();
+            self.sort_columns();
+            self.save("raise priority");
+        }
+    }
+
+    // lower priority of selected task
+    pub fn lower_priority(&mut self) {
+        if let Some(task) = self.selected_task_mut() {
+            task.priority = task.priority.lowered();
+            task.touch();
+            self.sort_columns();
+            self.save("lower priority");
+        }
+    }
+
+    // re-sort auto-sorted columns, keeping the selected task selected
+    fn sort_columns(&mut self) {
+        let selected_id = self.selected_task_id();
+        for column in &mut self.board_mut().columns {
+            column.sort_tasks();
+        }
+        if let Some(id) = selected_id {
+            self.select_task(&id);
         }
     }
 
@@ -302,9 +910,10 @@ impl App {
         let selected_idx = self.selected_index; // Capture before mutable borrow
         let column = self.board_mut().get_column_mut(current_column_idx).unwrap(); // Directly get mutable column
         if selected_idx < column.tasks.len() {
-            column.tasks.remove(selected_idx);
+            let removed = column.tasks.remove(selected_idx);
+            self.board_mut().remove_task_references(&removed.id);
             self.clamp_selection();
-            self.save();
+            self.save("delete task");
         }
     }
 
@@ -322,6 +931,13 @@ impl App {
         }
     }
 
+    pub fn start_setting_wip_limit(&mut self) {
+        if let Some(column) = self.board().get_column(self.selected_column) {
+            self.input_buffer = column.wip_limit.map_or(String::new(), |n| n.to_string());
+            self.input_mode = InputMode::SettingWipLimit;
+        }
+    }
+
     pub fn delete_column(&mut self) {
         let board_len = self.board().columns.len();
         if board_len <= 1 {
@@ -342,7 +958,34 @@ impl App {
                 self.selected_column = self.board().columns.len().saturating_sub(1);
             }
             self.clamp_selection();
-            self.save();
+            self.save("delete column");
+        }
+    }
+
+    // switch current column between manual order and sort-by-priority
+    pub fn toggle_column_sort(&mut self) {
+        let col_idx = self.selected_column; // Capture before mutable borrow
+        if let Some(column) = self.board_mut().get_column_mut(col_idx) {
+            column.sort = match column.sort {
+                ColumnSort::Manual => ColumnSort::Priority,
+                ColumnSort::Priority => ColumnSort::Manual,
+            };
+            self.sort_columns();
+            self.save("toggle column sort");
+        }
+    }
+
+    // make the current column the one blocked tasks may not move past (toggle back to default)
+    pub fn toggle_blocked_gate(&mut self) {
+        let col_idx = self.selected_column; // Capture before mutable borrow
+        let board = self.board_mut();
+        if let Some(column_id) = board.get_column(col_idx).map(|col| col.id.clone()) {
+            board.blocked_gate = if board.blocked_gate.as_ref() == Some(&column_id) {
+                None
+            } else {
+                Some(column_id)
+            };
+            self.save("toggle gate column");
         }
     }
 
@@ -351,7 +994,7 @@ impl App {
             let idx = self.selected_column;
             self.board_mut().columns.swap(idx, idx - 1);
             self.selected_column -= 1;
-            self.save();
+            self.save("move column");
         }
     }
 
@@ -360,7 +1003,7 @@ impl App {
             let idx = self.selected_column;
             self.board_mut().columns.swap(idx, idx + 1);
             self.selected_column += 1;
-            self.save();
+            self.save("move column");
         }
     }
 
@@ -415,54 +1058,94 @@ impl App {
         match self.input_mode {
             InputMode::AddingTask => {
                 if !self.input_buffer.is_empty() {
-                    let task = Task::new(self.input_buffer.clone());
+                    let mut task = Task::new(self.input_buffer.clone());
+                    let task_id = task.id.clone();
                     let selected_col_idx = self.selected_column; // Capture before mutable borrow
+                    let is_done = self.board().is_done_column(selected_col_idx);
                     let current_column = self.board_mut().get_column_mut(selected_col_idx).unwrap();
+                    task.enter_column(&current_column.id, is_done);
                     current_column.tasks.push(task);
-                    // Select the newly created task (last in the column)
-                    let column_len = current_column.tasks.len();
-                    if column_len > 0 {
-                        self.selected_index = column_len - 1;
-                        self.update_scroll();
-                    }
-                    self.save();
+                    current_column.sort_tasks();
+                    // Select the newly created task
+                    self.select_task(&task_id);
+                    self.save("add task");
                 }
             }
             InputMode::AddingTag => {
                 if !self.input_buffer.is_empty() {
                     let tag = self.input_buffer.clone();
-                    let current_column_idx = self.selected_column; // Capture before mutable borrow
-                    let selected_idx = self.selected_index; // Capture before mutable borrow
-                    let column = self.board_mut().get_column_mut(current_column_idx).unwrap();
-                    if selected_idx < column.tasks.len() {
-                        column.tasks[selected_idx].add_tag(tag);
-                        self.save();
+                    if let Some(task) = self.selected_task_mut() {
+                        task.add_tag(tag);
+                        task.touch();
+                        self.save("add tag");
                     }
                 }
             }
             InputMode::EditingTitle => {
                 if !self.input_buffer.is_empty() {
                     let title = self.input_buffer.clone();
-                    let current_column_idx = self.selected_column; // Capture before mutable borrow
-                    let selected_idx = self.selected_index; // Capture before mutable borrow
-                    let column = self.board_mut().get_column_mut(current_column_idx).unwrap();
-                    if selected_idx < column.tasks.len() {
-                        column.tasks[selected_idx].title = title;
-                        self.save();
+                    if let Some(task) = self.selected_task_mut() {
+                        task.title = title;
+                        task.touch();
+                        self.save("edit title");
                     }
                 }
                 self.input_mode = InputMode::ViewingTask;
                 self.input_buffer.clear();
                 return;
             }
+            InputMode::EditingDueDate => {
+                match dates::parse_due_date(&self.input_buffer, dates::today()) {
+                    Ok(due) => {
+                        if let Some(task) = self.selected_task_mut() {
+                            task.due = due;
+                            task.touch();
+                            self.sort_columns();
+                            self.save("edit due date");
+                        }
+                        self.input_mode = InputMode::ViewingTask;
+                        self.input_buffer.clear();
+                    }
+                    Err(message) => {
+                        self.input_buffer.clear();
+                        self.handle_error(message);
+                    }
+                }
+                return;
+            }
             InputMode::EditingDescription => {
                 let description = self.input_buffer.clone();
-                let current_column_idx = self.selected_column; // Capture before mutable borrow
-                let selected_idx = self.selected_index; // Capture before mutable borrow
-                let column = self.board_mut().get_column_mut(current_column_idx).unwrap();
-                if selected_idx < column.tasks.len() {
-                    column.tasks[selected_idx].description = description;
-                    self.save();
+                if let Some(task) = self.selected_task_mut() {
+                    task.description = description;
+                    task.touch();
+                    self.save("edit description");
+                }
+                self.input_mode = InputMode::ViewingTask;
+                self.input_buffer.clear();
+                return;
+            }
+            InputMode::AddingChecklistItem => {
+                if !self.input_buffer.is_empty() {
+                    let text = self.input_buffer.clone();
+                    if let Some(task) = self.selected_task_mut() {
+                        task.checklist.push(ChecklistItem::new(text));
+                        task.touch();
+                        let new_index = task.checklist.len() - 1;
+                        self.selected_checklist_index = new_index;
+                        self.save("add checklist item");
+                    }
+                }
+                self.input_mode = InputMode::ViewingTask;
+                self.input_buffer.clear();
+                return;
+            }
+            InputMode::AddingBlocker => {
+                if !self.input_buffer.is_empty() {
+                    if let Err(message) = self.add_blocker() {
+                        self.input_buffer.clear();
+                        self.handle_error(message);
+                        return;
+                    }
                 }
                 self.input_mode = InputMode::ViewingTask;
                 self.input_buffer.clear();
@@ -474,7 +1157,7 @@ impl App {
                     self.projects.push(new_project);
                     self.current_project = self.projects.len() - 1;
                     self.selected_project_index = self.current_project;
-                    self.save();
+                    self.save("add project");
                 }
                 self.input_mode = InputMode::ProjectList;
                 self.input_buffer.clear();
@@ -483,10 +1166,8 @@ impl App {
             InputMode::AddingColumn => {
                 if !self.input_buffer.is_empty() {
                     let name = self.input_buffer.clone();
-                    let id = name.to_lowercase().replace(" ", "_");
-                    let new_column = BoardColumn::new(id, name);
-                    self.board_mut().columns.push(new_column);
-                    self.save();
+                    self.board_mut().add_column(name);
+                    self.save("add column");
                 }
             }
             InputMode::RenamingColumn => {
@@ -495,7 +1176,35 @@ impl App {
                     let col_idx = self.selected_column; // Capture before mutable borrow
                     if let Some(column) = self.board_mut().get_column_mut(col_idx) {
                         column.name = name;
-                        self.save();
+                        self.save("rename column");
+                    }
+                }
+            }
+            InputMode::SettingWipLimit => {
+                let input = self.input_buffer.trim();
+                let limit = if input.is_empty() {
+                    Ok(None)
+                } else {
+                    input
+                        .parse::<usize>()
+                        .map(|n| if n == 0 { None } else { Some(n) })
+                };
+                match limit {
+                    Ok(limit) => {
+                        let col_idx = self.selected_column; // Capture before mutable borrow
+                        if let Some(column) = self.board_mut().get_column_mut(col_idx) {
+                            column.wip_limit = limit;
+                            self.save("set WIP limit");
+                        }
+                    }
+                    Err(_) => {
+                        let message = format!(
+                            "Invalid WIP limit '{}'. Enter a number, or leave empty to remove the limit.",
+                            input
+                        );
+                        self.input_buffer.clear();
+                        self.handle_error(message);
+                        return;
                     }
                 }
             }
@@ -504,6 +1213,8 @@ impl App {
             | InputMode::ViewingHelp
             | InputMode::ProjectList
             | InputMode::ConfirmingDelete
+            | InputMode::ConfirmingWipLimit
+            | InputMode::ConfirmingReload
             | InputMode::SelectingTheme
             | InputMode::ShowErrorInfo => {}
         }
@@ -516,6 +1227,8 @@ impl App {
             if self.selected_index < column.tasks.len() {
                 self.input_mode = InputMode::ViewingTask;
                 self.focused_field = TaskField::Title; // Reset to title when opening
+                self.selected_checklist_index = 0;
+                self.selected_link_index = 0;
             }
         }
     }
@@ -524,16 +1237,22 @@ impl App {
     pub fn next_field(&mut self) {
         self.focused_field = match self.focused_field {
             TaskField::Title => TaskField::Tags,
-            TaskField::Tags => TaskField::Description,
-            TaskField::Description => TaskField::Title,
+            TaskField::Tags => TaskField::DueDate,
+            TaskField::DueDate => TaskField::Description,
+            TaskField::Description => TaskField::Checklist,
+            TaskField::Checklist => TaskField::Dependencies,
+            TaskField::Dependencies => TaskField::Title,
         };
     }
 
     // cycle to previous field in task detail view
     pub fn previous_field(&mut self) {
         self.focused_field = match self.focused_field {
-            TaskField::Title => TaskField::Description,
-            TaskField::Description => TaskField::Tags,
+            TaskField::Title => TaskField::Dependencies,
+            TaskField::Dependencies => TaskField::Checklist,
+            TaskField::Checklist => TaskField::Description,
+            TaskField::Description => TaskField::DueDate,
+            TaskField::DueDate => TaskField::Tags,
             TaskField::Tags => TaskField::Title,
         }
     }
@@ -548,6 +1267,19 @@ impl App {
         }
     }
 
+    // start editing due date
+    pub fn start_editing_due_date(&mut self) {
+        if let Some(column) = self.board().get_column(self.selected_column) {
+            if self.selected_index < column.tasks.len() {
+                self.input_buffer = column.tasks[self.selected_index]
+                    .due
+                    .map(|due| due.format("%Y-%m-%d").to_string())
+                    .unwrap_or_default();
+                self.input_mode = InputMode::EditingDueDate;
+            }
+        }
+    }
+
     // start editing description
     pub fn start_editing_description(&mut self) {
         if let Some(column) = self.board().get_column(self.selected_column) {
@@ -558,17 +1290,175 @@ impl App {
         }
     }
 
+    // checklist management
+
+    pub fn start_adding_checklist_item(&mut self) {
+        if self.selected_task_id().is_some() {
+            self.input_mode = InputMode::AddingChecklistItem;
+            self.input_buffer.clear();
+        }
+    }
+
+    fn checklist_len(&self) -> usize {
+        self.board()
+            .get_column(self.selected_column)
+            .and_then(|col| col.tasks.get(self.selected_index))
+            .map_or(0, |task| task.checklist.len())
+    }
+
+    pub fn next_checklist_item(&mut self) {
+        if self.selected_checklist_index + 1 < self.checklist_len() {
+            self.selected_checklist_index += 1;
+        }
+    }
+
+    pub fn previous_checklist_item(&mut self) {
+        self.selected_checklist_index = self.selected_checklist_index.saturating_sub(1);
+    }
+
+    pub fn toggle_checklist_item(&mut self) {
+        let item_idx = self.selected_checklist_index; // Capture before mutable borrow
+        if let Some(task) = self.selected_task_mut() {
+            if let Some(item) = task.checklist.get_mut(item_idx) {
+                item.done = !item.done;
+                task.touch();
+                self.save("toggle checklist item");
+            }
+        }
+    }
+
+    pub fn delete_checklist_item(&mut self) {
+        let item_idx = self.selected_checklist_index; // Capture before mutable borrow
+        if let Some(task) = self.selected_task_mut() {
+            if item_idx < task.checklist.len() {
+                task.checklist.remove(item_idx);
+                task.touch();
+                self.save("delete checklist item");
+            }
+        }
+        let len = self.checklist_len();
+        if self.selected_checklist_index >= len {
+            self.selected_checklist_index = len.saturating_sub(1);
+        }
+    }
+
+    // move selected checklist item down (or up), selection follows
+    pub fn move_checklist_item(&mut self, down: bool) {
+        let item_idx = self.selected_checklist_index; // Capture before mutable borrow
+        let target_idx = if down {
+            item_idx + 1
+        } else if item_idx > 0 {
+            item_idx - 1
+        } else {
+            return;
+        };
+        if let Some(task) = self.selected_task_mut() {
+            if target_idx < task.checklist.len() && item_idx < task.checklist.len() {
+                task.checklist.swap(item_idx, target_idx);
+                task.touch();
+                self.selected_checklist_index = target_idx;
+                self.save("move checklist item");
+            }
+        }
+    }
+
+    // dependency management
+
+    // links of the selected task: "blocked by" entries first, then "blocks"
+    pub fn task_links(&self) -> Vec<(LinkKind, String)> {
+        let board = self.board();
+        let Some(task) = self
+            .selected_task_id()
+            .and_then(|id| board.get_task(&id).cloned())
+        else {
+            return Vec::new();
+        };
+        let blocked_by = task
+            .blocked_by
+            .iter()
+            .filter(|id| board.get_task(id).is_some())
+            .map(|id| (LinkKind::BlockedBy, id.clone()));
+        let blocks = board
+            .blocked_tasks(&task.id)
+            .into_iter()
+            .map(|t| (LinkKind::Blocks, t.id.clone()));
+        blocked_by.chain(blocks).collect()
+    }
+
+    pub fn start_adding_blocker(&mut self) {
+        if self.selected_task_id().is_some() {
+            self.input_mode = InputMode::AddingBlocker;
+            self.input_buffer.clear();
+        }
+    }
+
+    // make the selected task blocked by the task named in the input buffer
+    fn add_blocker(&mut self) -> Result<(), String> {
+        let task_id = self.selected_task_id().ok_or("No task selected")?;
+        let blocker_id = self.board().resolve_task(&self.input_buffer)?;
+        if self.board().would_create_cycle(&task_id, &blocker_id) {
+            return Err("That would create a dependency cycle".to_string());
+        }
+        if let Some(task) = self.board_mut().get_task_mut(&task_id) {
+            if !task.blocked_by.contains(&blocker_id) {
+                task.blocked_by.push(blocker_id);
+                task.touch();
+            }
+        }
+        self.save("add blocker");
+        Ok(())
+    }
+
+    pub fn next_link(&mut self) {
+        if self.selected_link_index + 1 < self.task_links().len() {
+            self.selected_link_index += 1;
+        }
+    }
+
+    pub fn previous_link(&mut self) {
+        self.selected_link_index = self.selected_link_index.saturating_sub(1);
+    }
+
+    // jump to the linked task, keeping the detail view open
+    pub fn open_selected_link(&mut self) {
+        if let Some((_, id)) = self.task_links().get(self.selected_link_index).cloned() {
+            self.select_task(&id);
+            self.selected_checklist_index = 0;
+            self.selected_link_index = 0;
+        }
+    }
+
+    // remove the selected link from whichever task holds it
+    pub fn remove_selected_link(&mut self) {
+        let Some(task_id) = self.selected_task_id() else {
+            return;
+        };
+        let Some((kind, other_id)) = self.task_links().get(self.selected_link_index).cloned()
+        else {
+            return;
+        };
+        let (holder_id, blocker_id) = match kind {
+            LinkKind::BlockedBy => (task_id, other_id),
+            LinkKind::Blocks => (other_id, task_id),
+        };
+        if let Some(holder) = self.board_mut().get_task_mut(&holder_id) {
+            holder.blocked_by.retain(|id| *id != blocker_id);
+            holder.touch();
+            self.save("remove dependency");
+        }
+        let len = self.task_links().len();
+        if self.selected_link_index >= len {
+            self.selected_link_index = len.saturating_sub(1);
+        }
+    }
+
     // remove tag by index
     pub fn remove_tag(&mut self, tag_index: usize) {
-        let current_column_idx = self.selected_column; // Capture before mutable borrow
-        let selected_idx = self.selected_index; // Capture before mutable borrow
-        if let Some(column) = self.board_mut().get_column_mut(current_column_idx) {
-            if selected_idx < column.tasks.len() {
-                let task = &mut column.tasks[selected_idx];
-                if tag_index < task.tags.len() {
-                    task.tags.remove(tag_index);
-                    self.save();
-                }
+        if let Some(task) = self.selected_task_mut() {
+            if tag_index < task.tags.len() {
+                task.tags.remove(tag_index);
+                task.touch();
+                self.save("remove tag");
             }
         }
     }
@@ -605,6 +1495,13 @@ impl App {
     }
 
     pub fn start_confirming_delete(&mut self) {
+        if self.is_repo_project(self.selected_project_index) {
+            self.handle_error(format!(
+                "This board belongs to the repository and is shared through git.\nTo remove it, delete the {} folder there.",
+                repo_board::BOARD_DIR
+            ));
+            return;
+        }
         if self.projects.len() > 1 {
             self.input_mode = InputMode::ConfirmingDelete;
         }
@@ -619,7 +1516,7 @@ impl App {
             if self.current_project >= self.projects.len() {
                 self.current_project = self.projects.len() - 1;
             }
-            self.save();
+            self.save("delete project");
         }
         self.input_mode = InputMode::ProjectList;
     }
@@ -628,6 +1525,12 @@ impl App {
         self.input_mode = InputMode::ProjectList;
     }
 
+    // is this the board committed to the current repository?
+    pub fn is_repo_project(&self, index: usize) -> bool {
+        self.repo_project.is_some()
+            && self.projects.get(index).map(|p| &p.id) == self.repo_project.as_ref()
+    }
+
     pub fn set_project_as_default(&mut self) {
         let project_name = self.projects[self.selected_project_index].name.clone();
         let mut config = storage::load_config();
@@ -704,7 +1607,7 @@ impl App {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::board::{Board, BoardColumn, Project, Task};
+    use crate::board::{Board, BoardColumn, ColumnSort, Priority, Project, Task};
 
     fn create_test_app() -> App {
         let board = Board {
@@ -716,15 +1619,21 @@ mod tests {
                         Task::new("Task 1".to_string()),
                         Task::new("Task 2".to_string()),
                     ],
+                    sort: ColumnSort::Manual,
+                    wip_limit: None,
                 },
                 BoardColumn {
                     id: "col2".to_string(),
                     name: "Column 2".to_string(),
                     tasks: vec![],
+                    sort: ColumnSort::Manual,
+                    wip_limit: None,
                 },
             ],
+            blocked_gate: None,
         };
         let project = Project {
+            id: "test_project".to_string(),
             name: "Test Project".to_string(),
             board,
         };
@@ -787,6 +1696,517 @@ mod tests {
         assert_eq!(app.board().columns[1].tasks.len(), 2);
     }
 
+    #[test]
+    fn test_select_task_by_id() {
+        let mut app = create_test_app();
+        let id = app.board().columns[0].tasks[1].id.clone();
+
+        // id follows the task across columns
+        app.selected_index = 1;
+        assert_eq!(app.selected_task_id(), Some(id.clone()));
+        app.move_task_forward();
+        app.selected_column = 0;
+        app.selected_index = 0;
+
+        assert!(app.select_task(&id));
+        assert_eq!(app.selected_column, 1);
+        assert_eq!(app.selected_index, 0);
+        assert!(!app.select_task("missing"));
+    }
+
+    #[test]
+    fn test_task_movement_records_timestamps() {
+        let mut app = create_test_app();
+
+        // Col 2 is the last column, so moving there completes the task
+        app.move_task_forward();
+        let task = &app.board().columns[1].tasks[0];
+        assert_eq!(task.column_history.len(), 1);
+        assert_eq!(task.column_history[0].column_id, "col2");
+        assert!(task.completed_at.is_some());
+
+        app.selected_column = 1;
+        app.move_task_backward();
+        let task = &app.board().columns[0].tasks[1];
+        assert_eq!(task.column_history.len(), 2);
+        assert!(task.completed_at.is_none());
+    }
+
+    #[test]
+    fn test_add_task_records_column() {
+        let mut app = create_test_app();
+        app.input_buffer = "New".to_string();
+        app.input_mode = InputMode::AddingTask;
+        app.submit_input();
+
+        let task = &app.board().columns[0].tasks[2];
+        assert_eq!(task.title, "New");
+        assert_eq!(task.column_history[0].column_id, "col1");
+        assert_eq!(app.selected_index, 2);
+    }
+
+    #[test]
+    fn test_edit_due_date() {
+        let mut app = create_test_app();
+        app.open_task();
+        app.start_editing_due_date();
+        assert_eq!(app.input_mode, InputMode::EditingDueDate);
+        assert!(app.input_buffer.is_empty());
+
+        app.input_buffer = "2026-11-03".to_string();
+        app.submit_input();
+        assert_eq!(app.input_mode, InputMode::ViewingTask);
+        assert_eq!(
+            app.board().columns[0].tasks[0].due,
+            chrono::NaiveDate::from_ymd_opt(2026, 11, 3)
+        );
+
+        // existing date is pre-filled, invalid input keeps the old date
+        app.start_editing_due_date();
+        assert_eq!(app.input_buffer, "2026-11-03");
+        app.input_buffer = "whenever".to_string();
+        app.submit_input();
+        assert_eq!(app.input_mode, InputMode::ShowErrorInfo);
+        assert!(app.board().columns[0].tasks[0].due.is_some());
+
+        // empty input clears it
+        app.input_mode = InputMode::ViewingTask;
+        app.start_editing_due_date();
+        app.input_buffer.clear();
+        app.submit_input();
+        assert!(app.board().columns[0].tasks[0].due.is_none());
+    }
+
+    #[test]
+    fn test_priority_and_column_sort() {
+        let mut app = create_test_app();
+
+        // raise Task 2 in a manual column: order is unchanged
+        app.selected_index = 1;
+        app.raise_priority();
+        app.raise_priority();
+        assert_eq!(app.board().columns[0].tasks[1].priority, Priority::Medium);
+        assert_eq!(app.board().columns[0].tasks[1].title, "Task 2");
+
+        // switching to priority sort moves it to the top and the selection follows
+        app.toggle_column_sort();
+        assert_eq!(app.board().columns[0].sort, ColumnSort::Priority);
+        assert_eq!(app.board().columns[0].tasks[0].title, "Task 2");
+        assert_eq!(app.selected_index, 0);
+
+        // back at equal priority the current order is kept (stable sort)
+        app.lower_priority();
+        app.lower_priority();
+        assert_eq!(app.board().columns[0].tasks[0].priority, Priority::None);
+        assert_eq!(app.board().columns[0].tasks[0].title, "Task 2");
+
+        app.toggle_column_sort();
+        assert_eq!(app.board().columns[0].sort, ColumnSort::Manual);
+    }
+
+    #[test]
+    fn test_checklist_editing() {
+        let mut app = create_test_app();
+        app.open_task();
+        for text in ["first", "second", "third"] {
+            app.start_adding_checklist_item();
+            app.input_buffer = text.to_string();
+            app.submit_input();
+        }
+        assert_eq!(app.input_mode, InputMode::ViewingTask);
+        assert_eq!(app.selected_checklist_index, 2);
+        let checklist = |app: &App| -> Vec<(String, bool)> {
+            app.board().columns[0].tasks[0]
+                .checklist
+                .iter()
+                .map(|item| (item.text.clone(), item.done))
+                .collect()
+        };
+
+        // toggle "third", then move it to the top
+        app.toggle_checklist_item();
+        app.move_checklist_item(false);
+        app.move_checklist_item(false);
+        app.move_checklist_item(false); // already at the top
+        assert_eq!(app.selected_checklist_index, 0);
+        assert_eq!(checklist(&app)[0], ("third".to_string(), true));
+        assert_eq!(app.board().columns[0].tasks[0].checklist_progress(), (1, 3));
+
+        // delete the last item, selection is clamped
+        app.next_checklist_item();
+        app.next_checklist_item();
+        app.next_checklist_item();
+        assert_eq!(app.selected_checklist_index, 2);
+        app.delete_checklist_item();
+        assert_eq!(app.selected_checklist_index, 1);
+        assert_eq!(
+            checklist(&app),
+            [("third".to_string(), true), ("first".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn test_dependencies() {
+        let mut app = create_test_app();
+        app.open_task();
+
+        // Task 1 blocked by Task 2
+        app.start_adding_blocker();
+        app.input_buffer = "task 2".to_string();
+        app.submit_input();
+        assert_eq!(app.input_mode, InputMode::ViewingTask);
+        let task2_id = app.board().columns[0].tasks[1].id.clone();
+        assert_eq!(app.board().columns[0].tasks[0].blocked_by.len(), 1);
+        assert_eq!(app.board().columns[0].tasks[0].blocked_by[0], task2_id);
+        assert_eq!(app.task_links(), [(LinkKind::BlockedBy, task2_id.clone())]);
+
+        // Task 2 blocked by Task 1 would be a cycle
+        app.open_selected_link();
+        assert_eq!(app.selected_task_id(), Some(task2_id.clone()));
+        assert_eq!(app.task_links()[0].0, LinkKind::Blocks);
+        app.start_adding_blocker();
+        app.input_buffer = "task 1".to_string();
+        app.submit_input();
+        assert_eq!(app.input_mode, InputMode::ShowErrorInfo);
+
+        // removing the "blocks" link from Task 2 clears Task 1's blocker
+        app.input_mode = InputMode::ViewingTask;
+        app.remove_selected_link();
+        assert!(app.board().columns[0].tasks[0].blocked_by.is_empty());
+        assert!(app.task_links().is_empty());
+    }
+
+    #[test]
+    fn test_blocked_task_cannot_pass_gate() {
+        let mut app = create_test_app();
+        let task2_id = app.board().columns[0].tasks[1].id.clone();
+        app.board_mut().columns[0].tasks[0]
+            .blocked_by
+            .push(task2_id);
+
+        // default gate is the first column
+        app.move_task_forward();
+        assert_eq!(app.input_mode, InputMode::ShowErrorInfo);
+        assert!(app.error_message.contains("blocked by 'Task 2'"));
+        assert_eq!(app.board().columns[0].tasks.len(), 2);
+
+        // with the gate on the last column, it can move
+        app.close_error_info();
+        app.selected_column = 1;
+        app.toggle_blocked_gate();
+        assert_eq!(app.board().blocked_gate.as_deref(), Some("col2"));
+        app.selected_column = 0;
+        app.move_task_forward();
+        assert_eq!(app.board().columns[1].tasks.len(), 1);
+
+        // deleting the blocker drops the reference
+        app.delete_task();
+        assert!(app.board().columns[1].tasks[0].blocked_by.is_empty());
+    }
+
+    #[test]
+    fn test_wip_limit_confirmation() {
+        let mut app = create_test_app();
+        app.selected_column = 1;
+        app.start_setting_wip_limit();
+        app.input_buffer = "1".to_string();
+        app.submit_input();
+        assert_eq!(app.board().columns[1].wip_limit, Some(1));
+
+        // first move fits
+        app.selected_column = 0;
+        app.move_task_forward();
+        assert_eq!(app.board().columns[1].tasks.len(), 1);
+
+        // second move needs confirmation; cancelling leaves the board alone
+        app.move_task_forward();
+        assert_eq!(app.input_mode, InputMode::ConfirmingWipLimit);
+        app.cancel_pending_move();
+        assert_eq!(app.board().columns[0].tasks.len(), 1);
+        assert_eq!(app.pending_move, None);
+
+        // confirming goes over the limit
+        app.move_task_forward();
+        app.confirm_pending_move();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(app.board().columns[1].tasks.len(), 2);
+        assert!(app.board().columns[1].is_over_wip_limit());
+
+        // empty input removes the limit, garbage is rejected
+        app.selected_column = 1;
+        app.start_setting_wip_limit();
+        assert_eq!(app.input_buffer, "1");
+        app.input_buffer = "lots".to_string();
+        app.submit_input();
+        assert_eq!(app.input_mode, InputMode::ShowErrorInfo);
+        assert_eq!(app.board().columns[1].wip_limit, Some(1));
+        app.start_setting_wip_limit();
+        app.input_buffer.clear();
+        app.submit_input();
+        assert_eq!(app.board().columns[1].wip_limit, None);
+    }
+
+    #[test]
+    fn test_reorder_within_column() {
+        let mut app = create_test_app();
+        app.move_task_up(); // already at the top
+        assert_eq!(app.board().columns[0].tasks[0].title, "Task 1");
+
+        app.move_task_down();
+        assert_eq!(app.board().columns[0].tasks[0].title, "Task 2");
+        assert_eq!(app.board().columns[0].tasks[1].title, "Task 1");
+        assert_eq!(app.selected_index, 1);
+        app.move_task_down(); // already at the bottom
+        assert_eq!(app.selected_index, 1);
+
+        app.move_task_up();
+        assert_eq!(app.board().columns[0].tasks[0].title, "Task 1");
+        assert_eq!(app.selected_index, 0);
+
+        // sorted columns refuse manual reordering
+        app.toggle_column_sort();
+        app.move_task_down();
+        assert_eq!(app.input_mode, InputMode::ShowErrorInfo);
+    }
+
+    #[test]
+    fn test_move_insert_position() {
+        let mut app = create_test_app();
+        app.board_mut().columns[1]
+            .tasks
+            .push(Task::new("Existing".to_string()));
+
+        assert_eq!(app.insert_position, InsertPosition::Bottom);
+        app.move_task_forward();
+        assert_eq!(app.board().columns[1].tasks[1].title, "Task 1");
+
+        app.cycle_insert_position();
+        assert_eq!(app.insert_position, InsertPosition::Top);
+        app.move_task_forward();
+        assert_eq!(app.board().columns[1].tasks[0].title, "Task 2");
+
+        // at selection: the task keeps its row
+        app.cycle_insert_position();
+        app.selected_column = 1;
+        app.selected_index = 2;
+        app.move_task_backward();
+        assert_eq!(app.board().columns[0].tasks[0].title, "Task 1");
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut app = create_test_app();
+        app.delete_task();
+        assert_eq!(app.board().columns[0].tasks.len(), 1);
+
+        app.undo();
+        assert_eq!(app.board().columns[0].tasks.len(), 2);
+        assert_eq!(app.board().columns[0].tasks[0].title, "Task 1");
+        assert_eq!(app.status_message.as_deref(), Some("Undid: delete task"));
+
+        app.redo();
+        assert_eq!(app.board().columns[0].tasks.len(), 1);
+        assert_eq!(app.status_message.as_deref(), Some("Redid: delete task"));
+
+        // a new action drops the redo history
+        app.undo();
+        app.move_task_forward();
+        app.redo();
+        assert_eq!(app.status_message.as_deref(), Some("Nothing to redo"));
+        assert_eq!(app.board().columns[1].tasks.len(), 1);
+
+        app.undo();
+        app.undo();
+        assert_eq!(app.status_message.as_deref(), Some("Nothing to undo"));
+        assert_eq!(app.board().columns[0].tasks.len(), 2);
+    }
+
+    #[test]
+    fn test_undo_column_and_project_changes() {
+        let mut app = create_test_app();
+        app.input_mode = InputMode::RenamingColumn;
+        app.input_buffer = "Backlog".to_string();
+        app.submit_input();
+        app.move_column_right();
+        assert_eq!(app.board().columns[1].name, "Backlog");
+
+        app.undo();
+        assert_eq!(app.board().columns[0].name, "Backlog");
+        app.undo();
+        assert_eq!(app.board().columns[0].name, "Column 1");
+
+        app.input_mode = InputMode::AddingProject;
+        app.input_buffer = "Second".to_string();
+        app.submit_input();
+        assert_eq!(app.projects.len(), 2);
+        app.undo();
+        assert_eq!(app.projects.len(), 1);
+        assert_eq!(app.current_project, 0);
+    }
+
+    #[test]
+    fn test_saving_blocked_until_forced() {
+        let mut app = create_test_app();
+        app.saving_blocked = true;
+        app.delete_task();
+        // history still works while blocked
+        assert_eq!(app.undo_stack.len(), 1);
+
+        app.force_save();
+        assert!(!app.saving_blocked);
+        assert_eq!(app.status_message.as_deref(), Some("Saving enabled"));
+    }
+
+    #[test]
+    fn test_undo_keeps_todo_txt_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut project = Project::new("Errands".to_string());
+        project.board.columns[0]
+            .tasks
+            .push(Task::new("Buy milk".to_string()));
+        let mut app = App::new_with_projects(vec![project]);
+        app.backend = Box::new(storage::JsonBackend::new(dir.path().join("projects")));
+        app.disable_saving = false;
+        let file = dir.path().join("todo.txt");
+        let project_id = app.projects[0].id.clone();
+        app.todo_txt_dir = dir.path().join("todo-txt");
+        app.todo_txt = vec![storage::TodoTxtSync {
+            project: project_id,
+            file: file.clone(),
+        }];
+        app.write_projects();
+
+        // the file gains a task, then an unrelated edit on the board is undone
+        let mut content = std::fs::read_to_string(&file).unwrap();
+        content.push_str("Call the bank\n");
+        std::fs::write(&file, content).unwrap();
+        app.raise_priority();
+        assert_eq!(app.undo_stack.len(), 1);
+        app.undo();
+        assert_eq!(app.board().columns[0].tasks[0].priority, Priority::None);
+
+        let titles: Vec<&str> = app.board().columns[0]
+            .tasks
+            .iter()
+            .map(|task| task.title.as_str())
+            .collect();
+        assert!(titles.contains(&"Call the bank"), "{:?}", titles);
+        assert!(std::fs::read_to_string(&file)
+            .unwrap()
+            .contains("Call the bank"));
+
+        // redoing brings the edit back without losing the file's task either
+        app.redo();
+        assert_eq!(app.board().columns[0].tasks[0].priority, Priority::Low);
+        assert_eq!(app.board().columns[0].tasks.len(), 2);
+    }
+
+    #[test]
+    fn test_reload_prompt_does_not_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = create_test_app();
+        app.backend = Box::new(storage::JsonBackend::new(dir.path().join("projects")));
+        app.disable_saving = false;
+        app.write_projects();
+
+        // another instance writes while we have unsaved changes
+        let mut other = storage::JsonBackend::new(dir.path().join("projects"));
+        let mut projects = storage::Backend::load(&mut other, false).unwrap();
+        projects[0].name = "Renamed elsewhere".to_string();
+        storage::Backend::save(&mut other, &projects).unwrap();
+        app.unsaved_changes = true;
+
+        app.check_for_external_changes();
+        assert_eq!(app.input_mode, InputMode::ConfirmingReload);
+        app.dismiss_reload();
+        app.check_for_external_changes();
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(app.unsaved_changes);
+
+        // read-only: keeping can't write, but mustn't ask again either
+        projects[0].name = "Renamed again".to_string();
+        storage::Backend::save(&mut other, &projects).unwrap();
+        app.lock_path = dir.path().join("tui-kanban.lock");
+        let _other_lock = storage::try_lock(&app.lock_path).unwrap().unwrap();
+        app.read_only = true;
+        app.check_for_external_changes();
+        assert_eq!(app.input_mode, InputMode::ConfirmingReload);
+        app.keep_local_changes();
+        app.check_for_external_changes();
+        assert_eq!(app.input_mode, InputMode::Normal);
+    }
+
+    #[test]
+    fn test_failed_save_leaves_todo_txt_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = App::new_with_projects(vec![Project::new("Errands".to_string())]);
+        // a file where the projects folder should be: saving fails
+        let projects_dir = dir.path().join("projects");
+        std::fs::write(&projects_dir, "").unwrap();
+        app.backend = Box::new(storage::JsonBackend::new(projects_dir.clone()));
+        app.disable_saving = false;
+        app.todo_txt_dir = dir.path().join("todo-txt");
+        let file = dir.path().join("todo.txt");
+        std::fs::write(&file, "Call the bank\n").unwrap();
+        app.todo_txt = vec![storage::TodoTxtSync {
+            project: app.projects[0].id.clone(),
+            file: file.clone(),
+        }];
+
+        app.write_projects();
+        assert!(app.unsaved_changes);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "Call the bank\n");
+        assert!(!app.todo_txt_dir.exists());
+
+        // once a save works, the file is written back, without adding the task twice
+        std::fs::remove_file(&projects_dir).unwrap();
+        app.write_projects();
+        assert!(!app.unsaved_changes);
+        assert_eq!(app.projects[0].count_tasks(), 1);
+        assert!(std::fs::read_to_string(&file).unwrap().contains("id:"));
+    }
+
+    #[test]
+    fn test_newer_file_is_never_saved_over() {
+        let mut app = create_test_app();
+        app.newer_file = true;
+        app.force_save();
+        assert!(app.newer_file);
+        assert!(app
+            .status_message
+            .as_deref()
+            .is_some_and(|message| message.contains("newer tui-kanban")));
+    }
+
+    #[test]
+    fn test_replace_projects_keeps_selection() {
+        let mut app = create_test_app();
+        app.selected_index = 1;
+        app.raise_priority();
+        let selected_id = app.selected_task_id().unwrap();
+
+        // someone else moved our task to the second column and added one before it
+        let mut projects = app.projects.clone();
+        let task = projects[0].board.columns[0].tasks.remove(1);
+        projects[0].board.columns[1]
+            .tasks
+            .push(Task::new("Theirs".to_string()));
+        projects[0].board.columns[1].tasks.push(task);
+
+        app.replace_projects(projects);
+        assert_eq!(app.selected_column, 1);
+        assert_eq!(app.selected_index, 1);
+        assert_eq!(app.selected_task_id(), Some(selected_id));
+        assert!(app.undo_stack.is_empty() && app.redo_stack.is_empty());
+
+        // task gone: stay in the same column
+        let mut projects = app.projects.clone();
+        projects[0].board.columns[1].tasks.clear();
+        app.replace_projects(projects);
+        assert_eq!(app.selected_column, 1);
+        assert_eq!(app.selected_index, 0);
+    }
+
     #[test]
     fn test_delete_task() {
         let mut app = create_test_app();
@@ -865,4 +2285,38 @@ mod tests {
         assert_eq!(app.board().columns[1].name, "Column 2");
         assert_eq!(app.selected_column, 1);
     }
+
+    #[test]
+    fn test_repo_board_opens_first() {
+        let projects: Vec<Project> = ["Home", "Work", "Repo"]
+            .iter()
+            .map(|name| {
+                let mut project = Project::new(name.to_string());
+                project.ensure_ids();
+                project
+            })
+            .collect();
+        let config: storage::Config =
+            serde_json::from_str(r#"{"default_project": "Work"}"#).unwrap();
+        let repo_id = projects[2].id.clone();
+
+        assert_eq!(App::determine_initial_project(&projects, &config, None), 1);
+        assert_eq!(
+            App::determine_initial_project(&projects, &config, Some(&repo_id)),
+            2
+        );
+    }
+
+    #[test]
+    fn test_repo_board_cannot_be_deleted() {
+        let mut app = create_test_app();
+        app.projects.push(Project::new("Other".to_string()));
+        app.repo_project = Some("test_project".to_string());
+        app.input_mode = InputMode::ProjectList;
+        app.selected_project_index = 0;
+
+        app.start_confirming_delete();
+        assert_eq!(app.input_mode, InputMode::ShowErrorInfo);
+        assert_eq!(app.projects.len(), 2);
+    }
 }
@@ -1,17 +1,153 @@
-use ratatui::style::Color;
+use crate::dates::DUE_SOON_DAYS;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// generate a short unique id for tasks and projects (12 hex chars)
+pub fn generate_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(nanos);
+    hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+    format!("{:012x}", hasher.finish() & 0xffff_ffff_ffff)
+}
 
 // simple task with title, tags, and description
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Task {
+    // empty for files saved before ids existed, backfilled on load
+    #[serde(default)]
+    pub id: String,
     pub title: String,
     pub tags: Vec<String>,
     pub description: String,
+    // timestamps are missing for tasks created before they were tracked
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub completed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub column_history: Vec<ColumnEntry>,
+    #[serde(default)]
+    pub due: Option<NaiveDate>,
+    #[serde(default)]
+    pub priority: Priority,
+    #[serde(default)]
+    pub checklist: Vec<ChecklistItem>,
+    // ids of tasks (in the same project) that must be done before this one
+    #[serde(default)]
+    pub blocked_by: Vec<String>,
+}
+
+// a single checklist entry inside a task
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChecklistItem {
+    pub text: String,
+    pub done: bool,
+}
+
+impl ChecklistItem {
+    pub fn new(text: String) -> Self {
+        Self { text, done: false }
+    }
+}
+
+// task priority, ordered from lowest to highest
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
+impl Priority {
+    pub fn raised(self) -> Self {
+        match self {
+            Self::None => Self::Low,
+            Self::Low => Self::Medium,
+            Self::Medium => Self::High,
+            Self::High | Self::Urgent => Self::Urgent,
+        }
+    }
+
+    pub fn lowered(self) -> Self {
+        match self {
+            Self::Urgent => Self::High,
+            Self::High => Self::Medium,
+            Self::Medium => Self::Low,
+            Self::Low | Self::None => Self::None,
+        }
+    }
+
+    // short marker drawn in front of the card title
+    pub fn marker(self) -> &'static str {
+        match self {
+            Self::None => "",
+            Self::Low => "↓",
+            Self::Medium => "!",
+            Self::High => "!!",
+            Self::Urgent => "!!!",
+        }
+    }
+
+    // inverse of `label`
+    pub fn from_label(label: &str) -> Option<Self> {
+        [
+            Self::None,
+            Self::Low,
+            Self::Medium,
+            Self::High,
+            Self::Urgent,
+        ]
+        .into_iter()
+        .find(|priority| priority.label() == label)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+            Self::Urgent => "urgent",
+        }
+    }
+}
+
+// how close a task is to its due date
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DueStatus {
+    NoDueDate,
+    Overdue,
+    DueSoon,
+    Later,
+}
+
+// records when a task entered a column
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ColumnEntry {
+    pub column_id: String,
+    pub entered_at: DateTime<Utc>,
 }
 
 // project contains a name and a board
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Project {
+    #[serde(default)]
+    pub id: String,
     pub name: String,
     pub board: Board,
 }
@@ -19,6 +155,7 @@ pub struct Project {
 impl Project {
     pub fn new(name: String) -> Self {
         Self {
+            id: generate_id(),
             name,
             board: Board::new(),
         }
@@ -28,40 +165,145 @@ impl Project {
     pub fn count_tasks(&self) -> usize {
         self.board.columns.iter().map(|col| col.tasks.len()).sum()
     }
+
+    // Assign ids to the project and any task that is missing one (or shares one
+    // with an earlier task, e.g. after a hand-edit). Returns true if anything changed.
+    pub fn ensure_ids(&mut self) -> bool {
+        let mut changed = false;
+        if self.id.is_empty() {
+            self.id = generate_id();
+            changed = true;
+        }
+
+        let mut seen = HashSet::new();
+        for column in &mut self.board.columns {
+            for task in &mut column.tasks {
+                if task.id.is_empty() || !seen.insert(task.id.clone()) {
+                    task.id = generate_id();
+                    seen.insert(task.id.clone());
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
 }
 
 impl Task {
     // Create task
     pub fn new(title: String) -> Self {
+        let now = Utc::now();
         Self {
+            id: generate_id(),
             title,
             tags: Vec::new(),
             description: String::new(),
+            created_at: Some(now),
+            updated_at: Some(now),
+            completed_at: None,
+            column_history: Vec::new(),
+            due: None,
+            priority: Priority::None,
+            checklist: Vec::new(),
+            blocked_by: Vec::new(),
         }
     }
 
+    // (done, total) checklist items
+    pub fn checklist_progress(&self) -> (usize, usize) {
+        let done = self.checklist.iter().filter(|item| item.done).count();
+        (done, self.checklist.len())
+    }
+
+    // classify the due date relative to today
+    pub fn due_status(&self, today: NaiveDate) -> DueStatus {
+        match self.due {
+            None => DueStatus::NoDueDate,
+            Some(due) if due < today => DueStatus::Overdue,
+            Some(due) if (due - today).num_days() <= DUE_SOON_DAYS => DueStatus::DueSoon,
+            Some(_) => DueStatus::Later,
+        }
+    }
+
+    // mark the task as modified now
+    pub fn touch(&mut self) {
+        self.updated_at = Some(Utc::now());
+    }
+
+    // record that the task entered a column; entering the done column completes it
+    pub fn enter_column(&mut self, column_id: &str, is_done: bool) {
+        let now = Utc::now();
+        self.column_history.push(ColumnEntry {
+            column_id: column_id.to_string(),
+            entered_at: now,
+        });
+        self.completed_at = if is_done { Some(now) } else { None };
+        self.updated_at = Some(now);
+    }
+
+    // when the task entered the column it is currently in
+    pub fn entered_current_column(&self) -> Option<DateTime<Utc>> {
+        self.column_history.last().map(|entry| entry.entered_at)
+    }
+
+    // time from creation to completion, if both are known
+    pub fn cycle_time(&self) -> Option<chrono::Duration> {
+        Some(self.completed_at? - self.created_at?)
+    }
+
     // add tags to the task
     pub fn add_tag(&mut self, tag: String) {
         if !self.tags.contains(&tag) {
             self.tags.push(tag);
         }
     }
+}
+
+// how a column orders its tasks
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnSort {
+    // tasks stay in the order they were added/moved
+    #[default]
+    Manual,
+    // highest priority first, then earliest due date
+    Priority,
+}
+
+// where a task lands when it moves to another column
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum InsertPosition {
+    #[default]
+    Bottom,
+    Top,
+    // same index the task had in its old column
+    Selection,
+}
+
+impl InsertPosition {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Bottom => Self::Top,
+            Self::Top => Self::Selection,
+            Self::Selection => Self::Bottom,
+        }
+    }
 
-    // return color for a specific tag
-    pub fn get_tag_color(tag: &str) -> Color {
-        match tag {
-            "urgent" => Color::Red,
-            "security" => Color::LightRed,
-            "bug" => Color::Yellow,
-            "feature" => Color::Green,
-            "performance" => Color::LightGreen,
-            "enhancement" => Color::Blue,
-            "User" => Color::LightBlue,
-            "Dev" => Color::Magenta,
-            "documentation" => Color::Cyan,
-            "design" => Color::LightCyan,
-            "refactor" => Color::LightYellow,
-            _ => Color::White,
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Bottom => "bottom",
+            Self::Top => "top",
+            Self::Selection => "selection",
+        }
+    }
+
+    // index to insert at in a column of `len` tasks, given the task's old index
+    pub fn index(self, len: usize, old_index: usize) -> usize {
+        match self {
+            Self::Bottom => len,
+            Self::Top => 0,
+            Self::Selection => old_index.min(len),
         }
     }
 }
@@ -72,6 +314,11 @@ pub struct BoardColumn {
     pub id: String,
     pub name: String,
     pub tasks: Vec<Task>,
+    #[serde(default)]
+    pub sort: ColumnSort,
+    // maximum number of tasks (work-in-progress limit), None means unlimited
+    #[serde(default)]
+    pub wip_limit: Option<usize>,
 }
 
 impl BoardColumn {
@@ -80,6 +327,34 @@ impl BoardColumn {
             id,
             name,
             tasks: Vec::new(),
+            sort: ColumnSort::Manual,
+            wip_limit: None,
+        }
+    }
+
+    // adding another task would exceed the WIP limit
+    pub fn is_at_wip_limit(&self) -> bool {
+        self.wip_limit
+            .is_some_and(|limit| self.tasks.len() >= limit)
+    }
+
+    pub fn is_over_wip_limit(&self) -> bool {
+        self.wip_limit.is_some_and(|limit| self.tasks.len() > limit)
+    }
+
+    // re-order tasks according to the column's sort setting (stable, no-op for manual)
+    pub fn sort_tasks(&mut self) {
+        if self.sort == ColumnSort::Priority {
+            self.tasks.sort_by(|a, b| {
+                b.priority
+                    .cmp(&a.priority)
+                    .then_with(|| match (a.due, b.due) {
+                        (Some(a_due), Some(b_due)) => a_due.cmp(&b_due),
+                        (Some(_), None) => std::cmp::Ordering::Less,
+                        (None, Some(_)) => std::cmp::Ordering::Greater,
+                        (None, None) => std::cmp::Ordering::Equal,
+                    })
+            });
         }
     }
 }
@@ -88,6 +363,9 @@ impl BoardColumn {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Board {
     pub columns: Vec<BoardColumn>,
+    // column id blocked tasks may not move past (first column if unset)
+    #[serde(default)]
+    pub blocked_gate: Option<String>,
 }
 
 impl Board {
@@ -100,9 +378,23 @@ impl Board {
                 BoardColumn::new("testing".to_string(), "Testing".to_string()),
                 BoardColumn::new("done".to_string(), "Done".to_string()),
             ],
+            blocked_gate: None,
         }
     }
 
+    // index of the column blocked tasks may not move past
+    pub fn blocked_gate_index(&self) -> usize {
+        self.blocked_gate
+            .as_ref()
+            .and_then(|id| self.columns.iter().position(|col| &col.id == id))
+            .unwrap_or(0)
+    }
+
+    // the last column counts as "done"
+    pub fn is_done_column(&self, index: usize) -> bool {
+        !self.columns.is_empty() && index == self.columns.len() - 1
+    }
+
     // get column by index (Read only)
     pub fn get_column(&self, index: usize) -> Option<&BoardColumn> {
         self.columns.get(index)
@@ -112,12 +404,175 @@ impl Board {
     pub fn get_column_mut(&mut self, index: usize) -> Option<&mut BoardColumn> {
         self.columns.get_mut(index)
     }
+
+    // append a column, its id made from the name (with a number added if
+    // another column has it already); returns its index
+    pub fn add_column(&mut self, name: String) -> usize {
+        let base = name.to_lowercase().replace(" ", "_");
+        let mut id = base.clone();
+        let mut n = 2;
+        while self.columns.iter().any(|col| col.id == id) {
+            id = format!("{}_{}", base, n);
+            n += 1;
+        }
+        self.columns.push(BoardColumn::new(id, name));
+        self.columns.len() - 1
+    }
+
+    // index of the column with the given id, or else name (ignoring case)
+    pub fn find_column(&self, id_or_name: &str) -> Option<usize> {
+        self.columns
+            .iter()
+            .position(|col| col.id == id_or_name)
+            .or_else(|| {
+                self.columns
+                    .iter()
+                    .position(|col| col.name.eq_ignore_ascii_case(id_or_name))
+            })
+    }
+
+    // find (column index, task index) of the task with the given id
+    pub fn find_task(&self, id: &str) -> Option<(usize, usize)> {
+        self.columns.iter().enumerate().find_map(|(col_idx, col)| {
+            col.tasks
+                .iter()
+                .position(|task| task.id == id)
+                .map(|task_idx| (col_idx, task_idx))
+        })
+    }
+
+    // get task by id (Read only)
+    pub fn get_task(&self, id: &str) -> Option<&Task> {
+        self.find_task(id)
+            .map(|(col_idx, task_idx)| &self.columns[col_idx].tasks[task_idx])
+    }
+
+    // a task is finished once it sits in the done column
+    pub fn is_task_done(&self, id: &str) -> bool {
+        self.find_task(id)
+            .is_some_and(|(col_idx, _)| self.is_done_column(col_idx))
+    }
+
+    // blockers of a task that are not done yet (unknown ids are ignored)
+    pub fn unfinished_blockers(&self, task: &Task) -> Vec<&Task> {
+        task.blocked_by
+            .iter()
+            .filter(|id| !self.is_task_done(id))
+            .filter_map(|id| self.get_task(id))
+            .collect()
+    }
+
+    pub fn is_blocked(&self, task: &Task) -> bool {
+        !self.unfinished_blockers(task).is_empty()
+    }
+
+    // why the task at (col_idx, task_idx) can't move to `target`: blocked
+    // tasks may not move forward past the gate column; None if it may move
+    pub fn blocked_move_message(
+        &self,
+        col_idx: usize,
+        task_idx: usize,
+        target: usize,
+    ) -> Option<String> {
+        let gate = self.blocked_gate_index();
+        if target <= col_idx || target <= gate {
+            return None;
+        }
+        let task = self.get_column(col_idx)?.tasks.get(task_idx)?;
+        let blockers = self.unfinished_blockers(task);
+        if blockers.is_empty() {
+            return None;
+        }
+        let titles: Vec<String> = blockers.iter().map(|t| format!("'{}'", t.title)).collect();
+        Some(format!(
+            "'{}' is blocked by {} and can't move past '{}' until they are done",
+            task.title,
+            titles.join(", "),
+            self.columns[gate].name
+        ))
+    }
+
+    // tasks that list the given task as a blocker
+    pub fn blocked_tasks(&self, id: &str) -> Vec<&Task> {
+        self.columns
+            .iter()
+            .flat_map(|col| col.tasks.iter())
+            .filter(|task| task.blocked_by.iter().any(|blocker| blocker == id))
+            .collect()
+    }
+
+    // would making `task_id` blocked by `blocker_id` create a dependency cycle?
+    pub fn would_create_cycle(&self, task_id: &str, blocker_id: &str) -> bool {
+        // walk everything the blocker (transitively) depends on
+        let mut stack = vec![blocker_id.to_string()];
+        let mut seen = HashSet::new();
+        while let Some(id) = stack.pop() {
+            if id == task_id {
+                return true;
+            }
+            if seen.insert(id.clone()) {
+                if let Some(task) = self.get_task(&id) {
+                    stack.extend(task.blocked_by.iter().cloned());
+                }
+            }
+        }
+        false
+    }
+
+    // drop references to a deleted task
+    pub fn remove_task_references(&mut self, id: &str) {
+        for column in &mut self.columns {
+            for task in &mut column.tasks {
+                task.blocked_by.retain(|blocker| blocker != id);
+            }
+        }
+    }
+
+    // resolve user input to a task id: exact id, id prefix, or a unique title match
+    pub fn resolve_task(&self, query: &str) -> Result<String, String> {
+        let query = query.trim();
+        let tasks: Vec<&Task> = self
+            .columns
+            .iter()
+            .flat_map(|col| col.tasks.iter())
+            .collect();
+        if let Some(task) = tasks.iter().find(|task| task.id == query) {
+            return Ok(task.id.clone());
+        }
+
+        let lowered = query.to_lowercase();
+        let mut matches: Vec<&&Task> = tasks
+            .iter()
+            .filter(|task| task.id.starts_with(query) || task.title.to_lowercase() == lowered)
+            .collect();
+        if matches.is_empty() {
+            matches = tasks
+                .iter()
+                .filter(|task| task.title.to_lowercase().contains(&lowered))
+                .collect();
+        }
+
+        match matches.as_slice() {
+            [task] => Ok(task.id.clone()),
+            [] => Err(format!("No task matches '{}'", query)),
+            _ => Err(format!(
+                "'{}' matches {} tasks, be more specific or use the task id",
+                query,
+                matches.len()
+            )),
+        }
+    }
+
+    // get task by id (Mutable)
+    pub fn get_task_mut(&mut self, id: &str) -> Option<&mut Task> {
+        self.find_task(id)
+            .map(|(col_idx, task_idx)| &mut self.columns[col_idx].tasks[task_idx])
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ratatui::style::Color;
 
     #[test]
     fn test_task_creation() {
@@ -125,6 +580,154 @@ mod tests {
         assert_eq!(task.title, "Test Task");
         assert!(task.tags.is_empty());
         assert!(task.description.is_empty());
+        assert_eq!(task.id.len(), 12);
+    }
+
+    #[test]
+    fn test_task_timestamps() {
+        let mut task = Task::new("Task".to_string());
+        assert!(task.created_at.is_some());
+        assert_eq!(task.created_at, task.updated_at);
+        assert!(task.completed_at.is_none());
+
+        task.enter_column("in_progress", false);
+        task.enter_column("done", true);
+        assert_eq!(task.column_history.len(), 2);
+        assert_eq!(task.column_history[1].column_id, "done");
+        assert_eq!(task.entered_current_column(), task.completed_at);
+        assert!(task.cycle_time().unwrap() >= chrono::Duration::zero());
+
+        // moving back out of done clears completion
+        task.enter_column("in_progress", false);
+        assert!(task.completed_at.is_none());
+        assert!(task.cycle_time().is_none());
+    }
+
+    #[test]
+    fn test_due_status() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        let mut task = Task::new("Task".to_string());
+        assert_eq!(task.due_status(today), DueStatus::NoDueDate);
+
+        task.due = NaiveDate::from_ymd_opt(2026, 10, 13);
+        assert_eq!(task.due_status(today), DueStatus::Overdue);
+        task.due = Some(today);
+        assert_eq!(task.due_status(today), DueStatus::DueSoon);
+        task.due = NaiveDate::from_ymd_opt(2026, 10, 16);
+        assert_eq!(task.due_status(today), DueStatus::DueSoon);
+        task.due = NaiveDate::from_ymd_opt(2026, 10, 17);
+        assert_eq!(task.due_status(today), DueStatus::Later);
+    }
+
+    #[test]
+    fn test_priority_raise_and_lower() {
+        assert_eq!(Priority::None.raised(), Priority::Low);
+        assert_eq!(Priority::Urgent.raised(), Priority::Urgent);
+        assert_eq!(Priority::High.lowered(), Priority::Medium);
+        assert_eq!(Priority::None.lowered(), Priority::None);
+        assert!(Priority::Urgent > Priority::Low);
+        assert_eq!(Priority::from_label("high"), Some(Priority::High));
+        assert_eq!(Priority::from_label("HIGH"), None);
+    }
+
+    #[test]
+    fn test_column_sort_by_priority_then_due() {
+        let mut col = BoardColumn::new("todo".to_string(), "To Do".to_string());
+        let mut task = |title: &str, priority: Priority, due: Option<(i32, u32, u32)>| {
+            let mut task = Task::new(title.to_string());
+            task.priority = priority;
+            task.due = due.and_then(|(y, m, d)| NaiveDate::from_ymd_opt(y, m, d));
+            col.tasks.push(task);
+        };
+        task("low", Priority::Low, None);
+        task("high late", Priority::High, Some((2026, 12, 1)));
+        task("high no due", Priority::High, None);
+        task("high soon", Priority::High, Some((2026, 11, 1)));
+
+        // manual columns keep their order
+        col.sort_tasks();
+        assert_eq!(col.tasks[0].title, "low");
+
+        col.sort = ColumnSort::Priority;
+        col.sort_tasks();
+        let titles: Vec<&str> = col.tasks.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, ["high soon", "high late", "high no due", "low"]);
+    }
+
+    #[test]
+    fn test_checklist_progress() {
+        let mut task = Task::new("Task".to_string());
+        assert_eq!(task.checklist_progress(), (0, 0));
+
+        task.checklist.push(ChecklistItem::new("one".to_string()));
+        task.checklist.push(ChecklistItem::new("two".to_string()));
+        task.checklist[1].done = true;
+        assert_eq!(task.checklist_progress(), (1, 2));
+    }
+
+    #[test]
+    fn test_old_tasks_load_without_timestamps() {
+        let task: Task =
+            serde_json::from_str(r#"{"title":"Old","tags":[],"description":""}"#).unwrap();
+        assert!(task.created_at.is_none());
+        assert!(task.column_history.is_empty());
+        assert_eq!(task.priority, Priority::None);
+        assert!(task.checklist.is_empty());
+    }
+
+    #[test]
+    fn test_ids_are_unique() {
+        let a = Task::new("A".to_string());
+        let b = Task::new("B".to_string());
+        assert_ne!(a.id, b.id);
+        assert_ne!(
+            Project::new("P1".to_string()).id,
+            Project::new("P2".to_string()).id
+        );
+    }
+
+    #[test]
+    fn test_ensure_ids_backfills_missing_and_duplicate_ids() {
+        let mut project = Project::new("P".to_string());
+        project.id.clear();
+        let mut missing = Task::new("Missing".to_string());
+        missing.id.clear();
+        let original = Task::new("Original".to_string());
+        let copy = original.clone();
+        project.board.columns[0].tasks = vec![missing, original.clone(), copy];
+
+        assert!(project.ensure_ids());
+        assert!(!project.id.is_empty());
+        let tasks = &project.board.columns[0].tasks;
+        assert!(!tasks[0].id.is_empty());
+        assert_eq!(tasks[1].id, original.id); // first occurrence keeps its id
+        assert_ne!(tasks[2].id, original.id);
+
+        // nothing left to fix
+        assert!(!project.ensure_ids());
+    }
+
+    #[test]
+    fn test_find_task_by_id() {
+        let mut board = Board::new();
+        let task = Task::new("Find me".to_string());
+        let id = task.id.clone();
+        board.columns[2].tasks.push(Task::new("Other".to_string()));
+        board.columns[2].tasks.push(task);
+
+        assert_eq!(board.find_task(&id), Some((2, 1)));
+        board.get_task_mut(&id).unwrap().title = "Found".to_string();
+        assert_eq!(board.columns[2].tasks[1].title, "Found");
+        assert_eq!(board.find_task("nope"), None);
+    }
+
+    #[test]
+    fn test_find_column_by_id_or_name() {
+        let board = Board::new();
+        assert_eq!(board.find_column("in_progress"), Some(1));
+        assert_eq!(board.find_column("in progress"), Some(1));
+        assert_eq!(board.find_column("DONE"), Some(3));
+        assert_eq!(board.find_column("backlog"), None);
     }
 
     #[test]
@@ -139,13 +742,6 @@ mod tests {
         assert!(task.tags.contains(&"urgent".to_string()));
     }
 
-    #[test]
-    fn test_tag_colors() {
-        assert_eq!(Task::get_tag_color("urgent"), Color::Red);
-        assert_eq!(Task::get_tag_color("feature"), Color::Green);
-        assert_eq!(Task::get_tag_color("unknown_tag"), Color::White);
-    }
-
     #[test]
     fn test_board_creation() {
         let board = Board::new();
@@ -154,11 +750,131 @@ mod tests {
         assert_eq!(board.columns[3].name, "Done");
     }
 
+    fn dependency_board() -> (Board, String, String, String) {
+        let mut board = Board::new();
+        let a = Task::new("Write spec".to_string());
+        let mut b = Task::new("Implement".to_string());
+        let mut c = Task::new("Release".to_string());
+        b.blocked_by.push(a.id.clone());
+        c.blocked_by.push(b.id.clone());
+        let ids = (a.id.clone(), b.id.clone(), c.id.clone());
+        board.columns[0].tasks = vec![a, b, c];
+        (board, ids.0, ids.1, ids.2)
+    }
+
+    #[test]
+    fn test_blocked_tasks() {
+        let (mut board, a, b, _) = dependency_board();
+        let task_b = board.get_task(&b).unwrap().clone();
+        assert!(board.is_blocked(&task_b));
+        assert_eq!(board.blocked_tasks(&a)[0].id, b);
+
+        // finishing the blocker unblocks
+        let blocker = board.columns[0].tasks.remove(0);
+        board.columns[3].tasks.push(blocker);
+        assert!(board.is_task_done(&a));
+        assert!(!board.is_blocked(&task_b));
+
+        // deleted blockers are dropped
+        board.columns[3].tasks.clear();
+        board.remove_task_references(&a);
+        assert!(board.get_task(&b).unwrap().blocked_by.is_empty());
+    }
+
+    #[test]
+    fn test_dependency_cycles() {
+        let (board, a, b, c) = dependency_board();
+        // c -> b -> a, so a blocked by c would close the loop
+        assert!(board.would_create_cycle(&a, &c));
+        assert!(board.would_create_cycle(&a, &a));
+        assert!(!board.would_create_cycle(&c, &a));
+        assert!(!board.would_create_cycle(&b, &a));
+    }
+
+    #[test]
+    fn test_resolve_task() {
+        let (board, a, _, c) = dependency_board();
+        assert_eq!(board.resolve_task(&a), Ok(a.clone()));
+        assert_eq!(board.resolve_task(&c[..6]), Ok(c.clone()));
+        assert_eq!(board.resolve_task("write SPEC"), Ok(a.clone()));
+        assert_eq!(board.resolve_task("rel"), Ok(c));
+        assert!(board.resolve_task("i").is_err()); // ambiguous
+        assert!(board.resolve_task("nothing").is_err());
+    }
+
+    #[test]
+    fn test_blocked_move_message() {
+        let mut board = Board::new();
+        let blocker = Task::new("Design".to_string());
+        let mut task = Task::new("Build".to_string());
+        task.blocked_by.push(blocker.id.clone());
+        board.columns[0].tasks.push(blocker);
+        board.columns[1].tasks.push(task);
+
+        let message = board.blocked_move_message(1, 0, 2).unwrap();
+        assert_eq!(
+            message,
+            "'Build' is blocked by 'Design' and can't move past 'To Do' until they are done"
+        );
+        // backwards is always fine, and so is anything up to the gate
+        assert_eq!(board.blocked_move_message(1, 0, 0), None);
+        board.blocked_gate = Some("testing".to_string());
+        assert_eq!(board.blocked_move_message(1, 0, 2), None);
+        assert!(board.blocked_move_message(1, 0, 3).is_some());
+
+        let blocker = board.columns[0].tasks.remove(0);
+        board.columns[3].tasks.push(blocker);
+        assert_eq!(board.blocked_move_message(1, 0, 3), None);
+    }
+
+    #[test]
+    fn test_blocked_gate_index() {
+        let mut board = Board::new();
+        assert_eq!(board.blocked_gate_index(), 0);
+        board.blocked_gate = Some("testing".to_string());
+        assert_eq!(board.blocked_gate_index(), 2);
+        board.blocked_gate = Some("gone".to_string());
+        assert_eq!(board.blocked_gate_index(), 0);
+    }
+
+    #[test]
+    fn test_wip_limit() {
+        let mut col = BoardColumn::new("wip".to_string(), "WIP".to_string());
+        assert!(!col.is_at_wip_limit());
+
+        col.wip_limit = Some(1);
+        assert!(!col.is_at_wip_limit());
+        col.tasks.push(Task::new("A".to_string()));
+        assert!(col.is_at_wip_limit());
+        assert!(!col.is_over_wip_limit());
+        col.tasks.push(Task::new("B".to_string()));
+        assert!(col.is_over_wip_limit());
+    }
+
+    #[test]
+    fn test_insert_position() {
+        assert_eq!(InsertPosition::Bottom.index(3, 1), 3);
+        assert_eq!(InsertPosition::Top.index(3, 1), 0);
+        assert_eq!(InsertPosition::Selection.index(3, 1), 1);
+        assert_eq!(InsertPosition::Selection.index(0, 4), 0);
+        assert_eq!(InsertPosition::Selection.next(), InsertPosition::Bottom);
+    }
+
     #[test]
     fn test_board_column_creation() {
         let col = BoardColumn::new("col_id".to_string(), "Column Name".to_string());
         assert_eq!(col.id, "col_id");
         assert_eq!(col.name, "Column Name");
         assert!(col.tasks.is_empty());
+        assert_eq!(col.wip_limit, None);
+    }
+
+    #[test]
+    fn test_add_column() {
+        let mut board = Board::new();
+        assert_eq!(board.add_column("Code Review".to_string()), 4);
+        assert_eq!(board.columns[4].id, "code_review");
+        board.add_column("code review".to_string());
+        assert_eq!(board.columns[5].id, "code_review_2");
     }
 }
@@ -0,0 +1,305 @@
+//! CSV with one row per task, for spreadsheets and reporting.
+//!
+//! Import reads the columns by their header, so a sheet may reorder, add or
+//! drop them; only `column` and `title` are needed. Times are local, as
+//! `YYYY-MM-DD HH:MM:SS`. A board column without tasks is exported as a row
+//! with an empty title, so it survives the round trip.
+
+use crate::board::{Board, Priority, Project, Task};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+const HEADER: [&str; 12] = [
+    "project",
+    "column",
+    "column_id",
+    "id",
+    "title",
+    "tags",
+    "priority",
+    "due",
+    "description",
+    "created",
+    "updated",
+    "completed",
+];
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+fn format_time(time: Option<DateTime<Utc>>) -> String {
+    time.map(|time| time.with_timezone(&Local).format(TIME_FORMAT).to_string())
+        .unwrap_or_default()
+}
+
+fn parse_time(value: &str) -> Result<Option<DateTime<Utc>>, String> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    NaiveDateTime::parse_from_str(value, TIME_FORMAT)
+        .ok()
+        .and_then(|time| Local.from_local_datetime(&time).earliest())
+        .map(|time| Some(time.with_timezone(&Utc)))
+        .ok_or_else(|| format!("'{}' is not a YYYY-MM-DD HH:MM:SS time", value))
+}
+
+/// the tasks of a project, one row each
+pub fn to_csv(project: &Project) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let mut write = |record: &[String]| writer.write_record(record).map_err(|e| e.to_string());
+    write(&HEADER.map(String::from))?;
+    for column in &project.board.columns {
+        if column.tasks.is_empty() {
+            let mut record = vec![String::new(); HEADER.len()];
+            record[..3].clone_from_slice(&[
+                project.name.clone(),
+                column.name.clone(),
+                column.id.clone(),
+            ]);
+            write(&record)?;
+        }
+        for task in &column.tasks {
+            let priority = match task.priority {
+                Priority::None => String::new(),
+                priority => priority.label().to_string(),
+            };
+            write(&[
+                project.name.clone(),
+                column.name.clone(),
+                column.id.clone(),
+                task.id.clone(),
+                task.title.clone(),
+                task.tags.join(", "),
+                priority,
+                task.due.map(|due| due.to_string()).unwrap_or_default(),
+                task.description.clone(),
+                format_time(task.created_at),
+                format_time(task.updated_at),
+                format_time(task.completed_at),
+            ])?;
+        }
+    }
+    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+/// read rows into a new project, adding a column for each column id (or
+/// else name) in the order they first appear; the project column is not used
+pub fn parse(content: &str, name: String) -> Result<Project, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(content.as_bytes());
+    let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+    let field = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim().eq_ignore_ascii_case(name))
+    };
+    let (Some(column_field), Some(title_field)) = (field("column"), field("title")) else {
+        return Err("the header needs a column and a title".to_string());
+    };
+    // written by to_csv, which lists every column in board order
+    let column_id_field = field("column_id");
+
+    let mut project = Project::new(name);
+    project.board = Board {
+        columns: Vec::new(),
+        blocked_gate: None,
+    };
+    let mut rows = Vec::new();
+    for (row, record) in reader.records().enumerate() {
+        // the header is line 1
+        let at = |e: String| format!("line {}: {}", row + 2, e);
+        let record = record.map_err(|e| at(e.to_string()))?;
+        let value = |field: Option<usize>| {
+            field
+                .and_then(|field| record.get(field))
+                .unwrap_or_default()
+                .trim()
+        };
+        let title = value(Some(title_field));
+        let column = value(Some(column_field));
+        let column_id = value(column_id_field);
+        if column.is_empty() {
+            if title.is_empty() {
+                continue;
+            }
+            return Err(at(format!("'{}' has no column", title)));
+        }
+
+        let board = &mut project.board;
+        let existing = match column_id {
+            "" => board
+                .columns
+                .iter()
+                .position(|col| col.name.eq_ignore_ascii_case(column)),
+            id => board.columns.iter().position(|col| col.id == id),
+        };
+        let col_idx = existing.unwrap_or_else(|| {
+            let col_idx = board.add_column(column.to_string());
+            if !column_id.is_empty() && !board.columns.iter().any(|col| col.id == column_id) {
+                board.columns[col_idx].id = column_id.to_string();
+            }
+            col_idx
+        });
+        // a column without tasks
+        if title.is_empty() {
+            continue;
+        }
+
+        let mut task = Task::new(title.to_string());
+        for tag in value(field("tags")).split(',') {
+            if !tag.trim().is_empty() {
+                task.add_tag(tag.trim().to_string());
+            }
+        }
+        let priority = value(field("priority"));
+        if !priority.is_empty() {
+            task.priority = Priority::from_label(priority)
+                .ok_or_else(|| at(format!("unknown priority '{}'", priority)))?;
+        }
+        let due = value(field("due"));
+        if !due.is_empty() {
+            let date = NaiveDate::parse_from_str(due, "%Y-%m-%d")
+                .map_err(|_| at(format!("'{}' is not a YYYY-MM-DD date", due)))?;
+            task.due = Some(date);
+        }
+        task.description = value(field("description")).to_string();
+        let times = [
+            parse_time(value(field("created"))).map_err(at)?,
+            parse_time(value(field("updated"))).map_err(at)?,
+            parse_time(value(field("completed"))).map_err(at)?,
+        ];
+        rows.push((project.board.columns[col_idx].id.clone(), task, times));
+    }
+
+    // The last column is done: in an export it already is. A sheet from
+    // elsewhere has its "Done" column moved last, or gets an empty one, so
+    // none of its tasks count as finished by where they happen to be
+    let board = &mut project.board;
+    if column_id_field.is_none() || board.columns.is_empty() {
+        let done = board.columns.iter().position(|col| {
+            col.id.eq_ignore_ascii_case("done") || col.name.eq_ignore_ascii_case("done")
+        });
+        match done {
+            Some(col_idx) => {
+                let column = board.columns.remove(col_idx);
+                board.columns.push(column);
+            }
+            None => {
+                board.add_column("Done".to_string());
+            }
+        }
+    }
+
+    for (column_id, mut task, [created, updated, completed]) in rows {
+        let col_idx = board.find_column(&column_id).unwrap_or_default();
+        let is_done = board.is_done_column(col_idx);
+        let column = &mut board.columns[col_idx];
+        task.enter_column(&column.id, is_done);
+        task.created_at = created.or(task.created_at);
+        task.updated_at = updated.or(task.updated_at);
+        if is_done {
+            task.completed_at = completed.or(task.completed_at);
+        }
+        column.tasks.push(task);
+    }
+    Ok(project)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut project = Project::new("Work".to_string());
+        let mut task = Task::new("Fix \"login\", again".to_string());
+        task.add_tag("bug".to_string());
+        task.add_tag("ui".to_string());
+        task.priority = Priority::Urgent;
+        task.due = NaiveDate::from_ymd_opt(2026, 10, 20);
+        task.description = "first line\nsecond, line".to_string();
+        project.board.columns[1].tasks.push(task);
+        project.board.columns[3]
+            .tasks
+            .push(Task::new("Shipped".to_string()));
+        project.board.columns[3].tasks[0].enter_column("done", true);
+
+        let csv = to_csv(&project).unwrap();
+        assert!(csv.starts_with("project,column,column_id,id,title,tags,"));
+        assert!(csv.contains("Work,In Progress,in_progress,"), "{}", csv);
+
+        let parsed = parse(&csv, "Copy".to_string()).unwrap();
+        let ids: Vec<&str> = parsed.board.columns.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["todo", "in_progress", "testing", "done"]);
+        let task = &parsed.board.columns[1].tasks[0];
+        assert_eq!(task.title, "Fix \"login\", again");
+        assert_eq!(task.tags, vec!["bug", "ui"]);
+        assert_eq!(task.priority, Priority::Urgent);
+        assert_eq!(task.description, "first line\nsecond, line");
+        assert!(task.completed_at.is_none());
+        let original = &project.board.columns[3].tasks[0];
+        let shipped = &parsed.board.columns[3].tasks[0];
+        assert_eq!(
+            format_time(shipped.completed_at),
+            format_time(original.completed_at)
+        );
+        assert_eq!(
+            format_time(shipped.created_at),
+            format_time(original.created_at)
+        );
+    }
+
+    #[test]
+    fn test_parse_spreadsheet() {
+        let content =
+            "Title,Status,Column\nWrite report,x,Backlog\nReview,,backlog\n,,\nPlan,,Next week\n";
+        let project = parse(content, "Sheet".to_string()).unwrap();
+        let names: Vec<&str> = project
+            .board
+            .columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Backlog", "Next week", "Done"]);
+        assert_eq!(project.board.columns[0].tasks.len(), 2);
+        assert_eq!(project.board.columns[1].id, "next_week");
+        assert!(project.board.columns[1].tasks[0].completed_at.is_none());
+
+        // a Done column goes last, wherever the sheet has it
+        let project = parse("title,column\nA,Done\nB,Later\n", "x".to_string()).unwrap();
+        assert_eq!(project.board.columns[1].name, "Done");
+        assert!(project.board.columns[1].tasks[0].completed_at.is_some());
+
+        let error = parse("title,column\nOrphan,\n", "x".to_string()).unwrap_err();
+        assert!(error.starts_with("line 2:"), "{}", error);
+        assert!(parse("name\nA\n", "x".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_round_trip_keeps_empty_columns() {
+        let mut project = Project::new("Work".to_string());
+        project.board.columns[0].name = "Backlog".to_string();
+        project.board.columns[1]
+            .tasks
+            .push(Task::new("Started".to_string()));
+
+        let parsed = parse(&to_csv(&project).unwrap(), "Copy".to_string()).unwrap();
+        let columns: Vec<(&str, &str)> = parsed
+            .board
+            .columns
+            .iter()
+            .map(|c| (c.id.as_str(), c.name.as_str()))
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                ("todo", "Backlog"),
+                ("in_progress", "In Progress"),
+                ("testing", "Testing"),
+                ("done", "Done")
+            ]
+        );
+        let started = &parsed.board.columns[1].tasks[0];
+        assert_eq!(started.title, "Started");
+        assert!(started.completed_at.is_none());
+    }
+}
@@ -0,0 +1,436 @@
+//! Line-oriented text format for a single project, meant for boards kept in git.
+//!
+//! ```text
+//! tui-kanban board 4
+//!
+//! project 8f3a9c0d1e2f
+//! name Website
+//!
+//! column todo
+//! name To Do
+//! task 0a1b2c3d4e5f
+//!
+//! task 0a1b2c3d4e5f
+//! title Write docs
+//! tag docs
+//! created 2026-10-18T09:30:00Z
+//! ```
+//!
+//! Every value sits on its own `key value` line and blocks are separated by a
+//! blank line. Columns list their tasks by id, in board order, and the tasks
+//! follow sorted by id. Moving a card only moves its id line between columns,
+//! and editing a card only touches its own block, so two people working on
+//! different cards rarely produce overlapping hunks.
+
+use crate::board::{
+    Board, BoardColumn, ChecklistItem, ColumnEntry, ColumnSort, Priority, Project, Task,
+};
+use crate::schema::CURRENT_SCHEMA_VERSION;
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use std::collections::HashMap;
+
+// first line of every file, followed by the schema version
+const HEADER: &str = "tui-kanban board";
+
+/// does this look like a board in the text format?
+pub fn is_text(content: &str) -> bool {
+    content.starts_with(HEADER)
+}
+
+/// serialize a project; the same project always gives the same text
+pub fn to_text(project: &Project) -> String {
+    let mut out = format!("{} {}\n", HEADER, CURRENT_SCHEMA_VERSION);
+
+    out.push('\n');
+    push_line(&mut out, "project", &project.id);
+    push_line(&mut out, "name", &project.name);
+    if let Some(gate) = &project.board.blocked_gate {
+        push_line(&mut out, "blocked-gate", gate);
+    }
+
+    for column in &project.board.columns {
+        out.push('\n');
+        push_line(&mut out, "column", &column.id);
+        push_line(&mut out, "name", &column.name);
+        if column.sort == ColumnSort::Priority {
+            push_line(&mut out, "sort", "priority");
+        }
+        if let Some(limit) = column.wip_limit {
+            push_line(&mut out, "wip-limit", &limit.to_string());
+        }
+        for task in &column.tasks {
+            push_line(&mut out, "task", &task.id);
+        }
+    }
+
+    let mut tasks: Vec<&Task> = project
+        .board
+        .columns
+        .iter()
+        .flat_map(|column| &column.tasks)
+        .collect();
+    tasks.sort_by(|a, b| a.id.cmp(&b.id));
+    for task in tasks {
+        out.push('\n');
+        push_task(&mut out, task);
+    }
+    out
+}
+
+fn push_task(out: &mut String, task: &Task) {
+    push_line(out, "task", &task.id);
+    push_line(out, "title", &task.title);
+    for tag in &task.tags {
+        push_line(out, "tag", tag);
+    }
+    if task.priority != Priority::None {
+        push_line(out, "priority", task.priority.label());
+    }
+    if let Some(due) = task.due {
+        push_line(out, "due", &due.to_string());
+    }
+    for id in &task.blocked_by {
+        push_line(out, "blocked-by", id);
+    }
+    for item in &task.checklist {
+        let mark = if item.done { "[x]" } else { "[ ]" };
+        push_line(out, "checklist", &format!("{} {}", mark, item.text));
+    }
+    // one line per line of text, so an edit shows up as just the lines it changed
+    if !task.description.is_empty() {
+        for line in task.description.split('\n') {
+            push_line(out, "description", line);
+        }
+    }
+    for (key, time) in [
+        ("created", task.created_at),
+        ("updated", task.updated_at),
+        ("completed", task.completed_at),
+    ] {
+        if let Some(time) = time {
+            push_line(out, key, &format_time(time));
+        }
+    }
+    for entry in &task.column_history {
+        let value = format!("{} {}", entry.column_id, format_time(entry.entered_at));
+        push_line(out, "entered", &value);
+    }
+}
+
+fn push_line(out: &mut String, key: &str, value: &str) {
+    out.push_str(key);
+    if !value.is_empty() {
+        out.push(' ');
+        out.push_str(&escape(value));
+    }
+    out.push('\n');
+}
+
+// keep every value on one line
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+fn parse_time(value: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|e| format!("invalid time '{}': {}", value, e))
+}
+
+/// parse a board written by `to_text`; errors name the offending line
+pub fn parse(content: &str) -> Result<Project, String> {
+    let mut lines = content.lines().enumerate();
+    let version = lines
+        .next()
+        .and_then(|(_, line)| line.strip_prefix(HEADER))
+        .and_then(|rest| rest.trim().parse::<u32>().ok())
+        .ok_or_else(|| format!("not a tui-kanban board (expected '{} <version>')", HEADER))?;
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(format!(
+            "version {} was written by a newer tui-kanban (this one reads up to {})",
+            version, CURRENT_SCHEMA_VERSION
+        ));
+    }
+
+    // split into blocks of (line number, key, value)
+    let mut blocks: Vec<Vec<(usize, &str, String)>> = Vec::new();
+    let mut current = Vec::new();
+    for (i, line) in lines {
+        if line.trim().is_empty() {
+            if !current.is_empty() {
+                blocks.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+        current.push((i + 1, key, unescape(value)));
+    }
+    if !current.is_empty() {
+        blocks.push(current);
+    }
+
+    let mut project: Option<Project> = None;
+    let mut column_tasks: Vec<Vec<(usize, String)>> = Vec::new();
+    let mut tasks: HashMap<String, Task> = HashMap::new();
+    for block in blocks {
+        let (line_no, kind, id) = &block[0];
+        let fields = &block[1..];
+        let at = |e: String| format!("line {}: {}", line_no, e);
+        match *kind {
+            "project" if project.is_none() => {
+                project = Some(parse_project(id, fields)?);
+            }
+            "column" => {
+                let project = project
+                    .as_mut()
+                    .ok_or_else(|| at("column before the project block".to_string()))?;
+                let (column, ids) = parse_column(id, fields)?;
+                project.board.columns.push(column);
+                column_tasks.push(ids);
+            }
+            "task" => {
+                let task = parse_task(id, fields)?;
+                if tasks.insert(id.clone(), task).is_some() {
+                    return Err(at(format!("task {} appears twice", id)));
+                }
+            }
+            other => return Err(at(format!("unexpected '{}' block", other))),
+        }
+    }
+
+    let mut project = project.ok_or("no project block")?;
+    for (column, ids) in project.board.columns.iter_mut().zip(column_tasks) {
+        for (line_no, id) in ids {
+            let task = tasks
+                .remove(&id)
+                .ok_or_else(|| format!("line {}: task {} has no block", line_no, id))?;
+            column.tasks.push(task);
+        }
+    }
+    if let Some(id) = tasks.keys().min() {
+        return Err(format!("task {} is not in any column", id));
+    }
+    Ok(project)
+}
+
+fn parse_project(id: &str, fields: &[(usize, &str, String)]) -> Result<Project, String> {
+    let mut project = Project {
+        id: id.to_string(),
+        name: String::new(),
+        board: Board {
+            columns: Vec::new(),
+            blocked_gate: None,
+        },
+    };
+    for (line_no, key, value) in fields {
+        match *key {
+            "name" => project.name = value.clone(),
+            "blocked-gate" => project.board.blocked_gate = Some(value.clone()),
+            other => return Err(unknown_key(*line_no, other)),
+        }
+    }
+    Ok(project)
+}
+
+// a column and the ids of its tasks, in order
+fn parse_column(
+    id: &str,
+    fields: &[(usize, &str, String)],
+) -> Result<(BoardColumn, Vec<(usize, String)>), String> {
+    let mut column = BoardColumn {
+        id: id.to_string(),
+        name: String::new(),
+        tasks: Vec::new(),
+        sort: ColumnSort::Manual,
+        wip_limit: None,
+    };
+    let mut ids = Vec::new();
+    for (line_no, key, value) in fields {
+        let at = |e: String| format!("line {}: {}", line_no, e);
+        match *key {
+            "name" => column.name = value.clone(),
+            "sort" => {
+                column.sort = match value.as_str() {
+                    "manual" => ColumnSort::Manual,
+                    "priority" => ColumnSort::Priority,
+                    other => return Err(at(format!("unknown sort '{}'", other))),
+                }
+            }
+            "wip-limit" => {
+                column.wip_limit = Some(
+                    value
+                        .parse()
+                        .map_err(|_| at(format!("invalid wip-limit '{}'", value)))?,
+                )
+            }
+            "task" => ids.push((*line_no, value.clone())),
+            other => return Err(unknown_key(*line_no, other)),
+        }
+    }
+    Ok((column, ids))
+}
+
+fn parse_task(id: &str, fields: &[(usize, &str, String)]) -> Result<Task, String> {
+    let mut task = Task::new(String::new());
+    task.id = id.to_string();
+    task.created_at = None;
+    task.updated_at = None;
+    let mut description: Vec<&str> = Vec::new();
+
+    for (line_no, key, value) in fields {
+        let at = |e: String| format!("line {}: {}", line_no, e);
+        match *key {
+            "title" => task.title = value.clone(),
+            "tag" => task.tags.push(value.clone()),
+            "priority" => {
+                task.priority = Priority::from_label(value)
+                    .ok_or_else(|| at(format!("unknown priority '{}'", value)))?
+            }
+            "due" => {
+                task.due = Some(
+                    value
+                        .parse::<NaiveDate>()
+                        .map_err(|_| at(format!("invalid due date '{}'", value)))?,
+                )
+            }
+            "blocked-by" => task.blocked_by.push(value.clone()),
+            "checklist" => {
+                let (done, text) = if let Some(text) = value.strip_prefix("[x] ") {
+                    (true, text)
+                } else if let Some(text) = value.strip_prefix("[ ] ") {
+                    (false, text)
+                } else {
+                    return Err(at(format!("invalid checklist item '{}'", value)));
+                };
+                task.checklist.push(ChecklistItem {
+                    text: text.to_string(),
+                    done,
+                });
+            }
+            "description" => description.push(value),
+            "created" => task.created_at = Some(parse_time(value).map_err(at)?),
+            "updated" => task.updated_at = Some(parse_time(value).map_err(at)?),
+            "completed" => task.completed_at = Some(parse_time(value).map_err(at)?),
+            "entered" => {
+                let (column_id, time) = value
+                    .rsplit_once(' ')
+                    .ok_or_else(|| at(format!("invalid entry '{}'", value)))?;
+                task.column_history.push(ColumnEntry {
+                    column_id: column_id.to_string(),
+                    entered_at: parse_time(time).map_err(at)?,
+                });
+            }
+            other => return Err(unknown_key(*line_no, other)),
+        }
+    }
+    task.description = description.join("\n");
+    Ok(task)
+}
+
+fn unknown_key(line_no: usize, key: &str) -> String {
+    format!("line {}: unknown key '{}'", line_no, key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_project() -> Project {
+        let mut project = Project::new("Website".to_string());
+        project.board.blocked_gate = Some("in_progress".to_string());
+        project.board.columns[1].wip_limit = Some(3);
+        project.board.columns[1].sort = ColumnSort::Priority;
+
+        let mut task = Task::new("Write docs\\drafts".to_string());
+        task.tags = vec!["docs".to_string(), "two words".to_string()];
+        task.priority = Priority::High;
+        task.due = NaiveDate::from_ymd_opt(2026, 11, 3);
+        task.description = "First line\n\n  indented\n".to_string();
+        task.checklist = vec![ChecklistItem {
+            text: "Outline".to_string(),
+            done: true,
+        }];
+        task.enter_column("in_progress", false);
+        let other = Task::new("Other".to_string());
+        task.blocked_by.push(other.id.clone());
+        project.board.columns[0].tasks.push(other);
+        project.board.columns[1].tasks.push(task);
+        project
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let project = sample_project();
+        let text = to_text(&project);
+        assert!(is_text(&text));
+
+        let parsed = parse(&text).unwrap();
+        assert_eq!(
+            serde_json::to_value(&parsed).unwrap(),
+            serde_json::to_value(&project).unwrap()
+        );
+        assert_eq!(to_text(&parsed), text);
+    }
+
+    #[test]
+    fn test_moving_a_task_changes_two_lines() {
+        let mut project = sample_project();
+        let before = to_text(&project);
+        let task = project.board.columns[0].tasks.remove(0);
+        let task_id = task.id.clone();
+        project.board.columns[3].tasks.push(task);
+        let after = to_text(&project);
+
+        let before: Vec<&str> = before.lines().collect();
+        let after: Vec<&str> = after.lines().collect();
+        assert_eq!(before.len(), after.len());
+        let moved = format!("task {}", task_id);
+        assert_eq!(before.iter().filter(|line| **line == moved).count(), 2);
+        // everything but the position of the id line is unchanged
+        let mut sorted_before = before.clone();
+        let mut sorted_after = after.clone();
+        sorted_before.sort();
+        sorted_after.sort();
+        assert_eq!(sorted_before, sorted_after);
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_line() {
+        let text = to_text(&Project::new("Website".to_string()));
+        let broken = text.replacen("name To Do", "colour red", 1);
+        let error = parse(&broken).unwrap_err();
+        assert!(error.contains("unknown key 'colour'"), "{}", error);
+        assert!(error.starts_with("line "), "{}", error);
+
+        let missing = format!("{}\ntask 123\n", text.trim_end());
+        assert!(parse(&missing)
+            .unwrap_err()
+            .contains("task 123 has no block"));
+        assert!(parse("{}").is_err());
+    }
+}
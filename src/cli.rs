@@ -0,0 +1,967 @@
+//! Headless subcommands for scripts, git hooks and launchers: load the
+//! projects, make one change, save and exit without opening the board.
+//!
+//! Queries (list, show, projects, export) only read, so they also work while
+//! the board is open elsewhere. Commands that change the board take the data
+//! directory lock like the board does, and refuse to run while it is open. A
+//! command whose projects were written by someone else while it ran fails
+//! rather than saving over that change.
+
+use crate::app::App;
+use crate::board::{Board, InsertPosition, Priority, Project, Task};
+use crate::board_csv;
+use crate::dates;
+use crate::importers;
+use crate::json_output::{self, ProjectInfo};
+use crate::markdown;
+use crate::repo_board;
+use crate::storage::{self, Config};
+use crate::todo_txt;
+use std::fs;
+use std::path::Path;
+
+pub const USAGE: &str = "\
+Usage: tui-kanban [--data-dir DIR] [COMMAND]
+
+Without a command, opens the board.
+
+Commands:
+  add TITLE [--column C] [--tag T]... [--priority P] [--due DATE] [--description TEXT]
+                            add a task, to the first column unless --column says otherwise
+  list [--column C] [--tag T] [--counts]
+                            list tasks by column, or only count them
+  move TASK COLUMN [--force]
+                            move a task to another column
+  done TASK [--force]       move a task to the last column
+  show TASK                 show everything about a task
+  projects                  list projects
+  export [FILE] [--format F]
+                            write the project to FILE, or print it
+  import FILE [--format F] [--name NAME] [--into PROJECT]
+                            add a project read from FILE, or its tasks to PROJECT
+
+list, show and projects take --json to print JSON for scripts and status bars.
+
+Every command but projects and import takes --project NAME, and otherwise uses
+the project tui-kanban would open. TASK is a task id, the start of one, or (part of) its
+title. A COLUMN is an id or name. --force moves a task past a full WIP limit.
+Export and import formats: markdown (the Obsidian Kanban layout) and csv.
+Import also reads the JSON exports of trello, github (issues or a project) and
+taskwarrior (task export). Without --format it follows the file extension, or
+for JSON, what the file looks like.
+
+Other commands:
+  --init-board              create a board for the repository in the current directory
+  --restore-backup [N]      list backups, or restore one
+  merge BASE OURS THEIRS    git merge driver for .tui-kanban/board.kanban
+";
+
+const COMMANDS: [&str; 8] = [
+    "add", "list", "move", "done", "show", "projects", "export", "import",
+];
+
+// commands that only read the projects: no lock, no todo.txt sync, no save
+const QUERIES: [&str; 4] = ["list", "show", "projects", "export"];
+
+pub fn is_command(name: &str) -> bool {
+    COMMANDS.contains(&name)
+}
+
+// command line options, after the command name
+struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, String)>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    // `options` take a value (`--tag bug` or `--tag=bug`), `flags` don't
+    fn parse(args: &[String], options: &[&str], flags: &[&str]) -> Result<Self, String> {
+        let mut parsed = Args {
+            positional: Vec::new(),
+            options: Vec::new(),
+            flags: Vec::new(),
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                parsed.positional.push(arg.clone());
+                continue;
+            };
+            if name.is_empty() {
+                // everything after `--` is positional, even if it starts with --
+                parsed.positional.extend(args.by_ref().cloned());
+                break;
+            }
+            let (name, inline_value) = match name.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (name, None),
+            };
+            if flags.contains(&name) && inline_value.is_none() {
+                parsed.flags.push(name.to_string());
+            } else if options.contains(&name) {
+                let value = match inline_value {
+                    Some(value) => value,
+                    None => args
+                        .next()
+                        .cloned()
+                        .ok_or_else(|| format!("--{} needs a value", name))?,
+                };
+                parsed.options.push((name.to_string(), value));
+            } else {
+                return Err(format!("unknown option --{}", name));
+            }
+        }
+        Ok(parsed)
+    }
+
+    // last value given for an option
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(option, _)| option == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn all(&self, name: &str) -> Vec<&str> {
+        self.options
+            .iter()
+            .filter(|(option, _)| option == name)
+            .map(|(_, value)| value.as_str())
+            .collect()
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|flag| flag == name)
+    }
+}
+
+/// run a headless command, printing its result
+pub fn run(command: &str, args: &[String]) -> Result<(), String> {
+    let args = match command {
+        "add" => Args::parse(
+            args,
+            &["project", "column", "tag", "priority", "due", "description"],
+            &[],
+        ),
+        "list" => Args::parse(args, &["project", "column", "tag"], &["json", "counts"]),
+        "move" | "done" => Args::parse(args, &["project"], &["force"]),
+        "show" => Args::parse(args, &["project"], &["json"]),
+        "projects" => Args::parse(args, &[], &["json"]),
+        "export" => Args::parse(args, &["project", "format"], &[]),
+        "import" => Args::parse(args, &["format", "name", "into"], &[]),
+        _ => Args::parse(args, &[], &[]),
+    }?;
+
+    let query = QUERIES.contains(&command);
+    // Held until the command has saved
+    let _lock = if query { None } else { lock_data_dir()? };
+
+    let mut config = storage::load_config();
+    let repo_board::Opened {
+        mut backend,
+        projects,
+        repo_project,
+    } = repo_board::open_projects(&config, query);
+    let mut projects = projects.map_err(|e| e.to_string())?;
+    if query {
+        let (output, _) = execute(
+            command,
+            &args,
+            &mut projects,
+            &config,
+            repo_project.as_deref(),
+        )?;
+        print!("{}", output);
+        return Ok(());
+    }
+
+    let loaded = backend.stamp();
+    let mut syncs = config.todo_txt.clone();
+    let todo_txt_dir = storage::get_todo_txt_dir();
+    let synced = todo_txt::merge_all(&mut projects, &mut syncs, &todo_txt_dir, &[]);
+
+    let (output, changed) = execute(
+        command,
+        &args,
+        &mut projects,
+        &config,
+        repo_project.as_deref(),
+    )?;
+    for warning in &synced.warnings {
+        eprintln!("tui-kanban: {}", warning);
+    }
+    if synced.config_changed {
+        config.todo_txt = syncs;
+        if let Err(e) = storage::save_config(&config) {
+            eprintln!("tui-kanban: could not save config.json: {}", e);
+        }
+    }
+    // Don't start over on top of a board that was set aside as corrupt
+    let pending = backend.pending_corrupt();
+    if let Some(path) = pending.first().filter(|_| changed) {
+        return Err(format!(
+            "not saved: {} was set aside as corrupt. Fix it and move it back, restore a backup with tui-kanban --restore-backup, or open tui-kanban and press Ctrl+s to start over.",
+            path.display()
+        ));
+    }
+    if pending.is_empty() {
+        if changed || synced.changed {
+            save_unless_changed(backend.as_mut(), loaded, &projects)?;
+        }
+        // Only once saved, so a failed save leaves the files as they were
+        for warning in todo_txt::commit_all(&projects, &synced.merged) {
+            eprintln!("tui-kanban: {}", warning);
+        }
+    }
+    print!("{}", output);
+    Ok(())
+}
+
+// take the lock an open board holds, so a change isn't made behind its back;
+// without a lock file at all, e.g. a read-only data dir, run unlocked like the board
+fn lock_data_dir() -> Result<Option<storage::DataLock>, String> {
+    let lock_path = storage::get_lock_path();
+    match storage::try_lock(&lock_path) {
+        Ok(Some(lock)) => Ok(Some(lock)),
+        Ok(None) => {
+            let holder = storage::lock_holder_pid(&lock_path)
+                .map(|pid| format!(" (pid {})", pid))
+                .unwrap_or_default();
+            Err(format!(
+                "the board is open in another tui-kanban{}; close it and run this again",
+                holder
+            ))
+        }
+        Err(_) => Ok(None),
+    }
+}
+
+// save, unless someone else wrote the projects since they were loaded
+fn save_unless_changed(
+    backend: &mut dyn storage::Backend,
+    loaded: Option<storage::FileStamp>,
+    projects: &[Project],
+) -> Result<(), String> {
+    if backend.stamp() != loaded {
+        return Err("not saved: the projects changed on disk meanwhile, run it again".to_string());
+    }
+    backend
+        .save(projects)
+        .map_err(|e| format!("could not save: {}", e))
+}
+
+// carry out a command on the loaded projects; returns what to print and
+// whether the projects changed
+fn execute(
+    command: &str,
+    args: &Args,
+    projects: &mut Vec<Project>,
+    config: &Config,
+    repo_project: Option<&str>,
+) -> Result<(String, bool), String> {
+    let default_index = App::determine_initial_project(projects, config, repo_project);
+    match command {
+        "projects" => {
+            let output = list_projects(projects, default_index, repo_project, args.flag("json"));
+            return Ok((output, false));
+        }
+        "import" => {
+            let [path] = &args.positional[..] else {
+                return Err("usage: tui-kanban import FILE".to_string());
+            };
+            let project = import_project(Path::new(path), args)?;
+            let output = match args.option("into") {
+                Some(name) => {
+                    let index = find_project(projects, name)?;
+                    let target = &mut projects[index];
+                    let count = import_into(target, project);
+                    format!("Imported {} tasks into '{}'\n", count, target.name)
+                }
+                None => {
+                    let output = format!(
+                        "Imported '{}' with {} tasks\n",
+                        project.name,
+                        project.count_tasks()
+                    );
+                    projects.push(project);
+                    output
+                }
+            };
+            return Ok((output, true));
+        }
+        _ => {}
+    }
+
+    let index = match args.option("project") {
+        Some(name) => find_project(projects, name)?,
+        None => default_index,
+    };
+    let project = &mut projects[index];
+    match command {
+        "add" => add_task(project, args).map(|output| (output, true)),
+        "list" => list_tasks(project, args).map(|output| (output, false)),
+        "move" => {
+            let [task, column] = &args.positional[..] else {
+                return Err("usage: tui-kanban move TASK COLUMN".to_string());
+            };
+            let target = find_column(&project.board, column)?;
+            move_task(
+                project,
+                task,
+                target,
+                config.insert_position,
+                args.flag("force"),
+            )
+            .map(|output| (output, true))
+        }
+        "done" => {
+            let [task] = &args.positional[..] else {
+                return Err("usage: tui-kanban done TASK".to_string());
+            };
+            let target = project.board.columns.len().saturating_sub(1);
+            move_task(
+                project,
+                task,
+                target,
+                config.insert_position,
+                args.flag("force"),
+            )
+            .map(|output| (output, true))
+        }
+        "show" => {
+            let [task] = &args.positional[..] else {
+                return Err("usage: tui-kanban show TASK".to_string());
+            };
+            let id = project.board.resolve_task(task)?;
+            let (col_idx, task_idx) = project
+                .board
+                .find_task(&id)
+                .ok_or_else(|| format!("no task {}", id))?;
+            let output = if args.flag("json") {
+                json_output::task(project, col_idx, task_idx, dates::today())
+            } else {
+                show_task(&project.board, col_idx, task_idx)
+            };
+            Ok((output, false))
+        }
+        "export" => match &args.positional[..] {
+            [] => Ok((
+                export_project(project, Format::pick(args, None, None)?)?,
+                false,
+            )),
+            [path] => {
+                let path = Path::new(path);
+                let content = export_project(project, Format::pick(args, Some(path), None)?)?;
+                storage::write_atomic(path, content.as_bytes())
+                    .map_err(|e| format!("could not write {}: {}", path.display(), e))?;
+                let output = format!("Exported '{}' to {}\n", project.name, path.display());
+                Ok((output, false))
+            }
+            _ => Err("usage: tui-kanban export [FILE]".to_string()),
+        },
+        other => Err(format!("unknown command '{}'", other)),
+    }
+}
+
+// formats of export and import
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Markdown,
+    Csv,
+    // import only
+    Trello,
+    Github,
+    Taskwarrior,
+}
+
+impl Format {
+    // --format, or else the file extension, or else what an imported file
+    // looks like; Markdown when none of them say
+    fn pick(args: &Args, path: Option<&Path>, content: Option<&str>) -> Result<Self, String> {
+        if let Some(name) = args.option("format") {
+            return Self::from_name(name).ok_or_else(|| {
+                format!(
+                    "unknown format '{}' (markdown, csv, trello, github, taskwarrior)",
+                    name
+                )
+            });
+        }
+        let extension = path
+            .and_then(|path| path.extension())
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default();
+        Ok(Self::from_name(&extension.to_lowercase())
+            .or_else(|| {
+                content
+                    .and_then(importers::detect)
+                    .and_then(Self::from_name)
+            })
+            .unwrap_or(Self::Markdown))
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "markdown" | "md" => Some(Self::Markdown),
+            "csv" => Some(Self::Csv),
+            "trello" => Some(Self::Trello),
+            "github" => Some(Self::Github),
+            "taskwarrior" => Some(Self::Taskwarrior),
+            _ => None,
+        }
+    }
+}
+
+fn export_project(project: &Project, format: Format) -> Result<String, String> {
+    match format {
+        Format::Markdown => Ok(markdown::to_markdown(project)),
+        Format::Csv => board_csv::to_csv(project),
+        Format::Trello | Format::Github | Format::Taskwarrior => {
+            Err("trello, github and taskwarrior can only be imported".to_string())
+        }
+    }
+}
+
+// a new project from a file, named after the file unless --name says otherwise
+fn import_project(path: &Path, args: &Args) -> Result<Project, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+    // used unless the file names its board
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Imported".to_string());
+    let project = match Format::pick(args, Some(path), Some(&content))? {
+        Format::Markdown => markdown::parse(&content, name),
+        Format::Csv => board_csv::parse(&content, name),
+        Format::Trello => importers::trello(&content, name),
+        Format::Github => importers::github(&content, name),
+        Format::Taskwarrior => importers::taskwarrior(&content, name),
+    };
+    let mut project = project.map_err(|e| format!("{}: {}", path.display(), e))?;
+    if let Some(name) = args.option("name") {
+        project.name = name.to_string();
+    }
+    Ok(project)
+}
+
+// add the tasks of an imported project to an existing one, putting them in
+// the column of the same name and adding the columns it doesn't have;
+// returns how many tasks were added
+fn import_into(target: &mut Project, imported: Project) -> usize {
+    let mut count = 0;
+    for column in imported.board.columns {
+        let board = &mut target.board;
+        let col_idx = match board
+            .columns
+            .iter()
+            .position(|col| col.name.eq_ignore_ascii_case(&column.name))
+        {
+            Some(col_idx) => col_idx,
+            // nothing to bring over, e.g. the empty Done column of a sheet
+            None if column.tasks.is_empty() => continue,
+            None => board.add_column(column.name),
+        };
+        let is_done = board.is_done_column(col_idx);
+        let target_column = &mut board.columns[col_idx];
+        for mut task in column.tasks {
+            if let Some(entry) = task.column_history.last_mut() {
+                entry.column_id = target_column.id.clone();
+            }
+            if !is_done {
+                task.completed_at = None;
+            } else if task.completed_at.is_none() {
+                task.completed_at = Some(chrono::Utc::now());
+            }
+            target_column.tasks.push(task);
+            count += 1;
+        }
+        target_column.sort_tasks();
+    }
+    target.ensure_ids();
+    count
+}
+
+fn find_project(projects: &[Project], name: &str) -> Result<usize, String> {
+    projects
+        .iter()
+        .position(|p| p.id == name)
+        .or_else(|| projects.iter().position(|p| p.name == name))
+        .or_else(|| {
+            projects
+                .iter()
+                .position(|p| p.name.eq_ignore_ascii_case(name))
+        })
+        .ok_or_else(|| format!("no project named '{}'", name))
+}
+
+fn find_column(board: &Board, id_or_name: &str) -> Result<usize, String> {
+    board.find_column(id_or_name).ok_or_else(|| {
+        let names: Vec<&str> = board.columns.iter().map(|col| col.name.as_str()).collect();
+        format!("no column '{}' (columns: {})", id_or_name, names.join(", "))
+    })
+}
+
+fn add_task(project: &mut Project, args: &Args) -> Result<String, String> {
+    let title = args.positional.join(" ");
+    if title.trim().is_empty() {
+        return Err("usage: tui-kanban add TITLE".to_string());
+    }
+    let col_idx = match args.option("column") {
+        Some(column) => find_column(&project.board, column)?,
+        None => 0,
+    };
+
+    let mut task = Task::new(title);
+    for tag in args.all("tag") {
+        task.add_tag(tag.to_string());
+    }
+    if let Some(priority) = args.option("priority") {
+        task.priority = Priority::from_label(priority).ok_or_else(|| {
+            format!(
+                "unknown priority '{}' (none, low, medium, high, urgent)",
+                priority
+            )
+        })?;
+    }
+    if let Some(due) = args.option("due") {
+        task.due = dates::parse_due_date(due, dates::today())?;
+    }
+    if let Some(description) = args.option("description") {
+        task.description = description.to_string();
+    }
+
+    let is_done = project.board.is_done_column(col_idx);
+    let column = project
+        .board
+        .get_column_mut(col_idx)
+        .ok_or("the project has no columns")?;
+    task.enter_column(&column.id, is_done);
+    let output = format!("Added {} '{}' to {}\n", task.id, task.title, column.name);
+    column.tasks.push(task);
+    column.sort_tasks();
+    Ok(output)
+}
+
+fn move_task(
+    project: &mut Project,
+    query: &str,
+    target: usize,
+    insert_position: InsertPosition,
+    force: bool,
+) -> Result<String, String> {
+    let id = project.board.resolve_task(query)?;
+    let (col_idx, task_idx) = project
+        .board
+        .find_task(&id)
+        .ok_or_else(|| format!("no task {}", id))?;
+    let board = &project.board;
+    let task = &board.columns[col_idx].tasks[task_idx];
+    let Some(target_column) = board.get_column(target) else {
+        return Err("the project has no columns".to_string());
+    };
+    if target == col_idx {
+        return Err(format!(
+            "'{}' is already in {}",
+            task.title, target_column.name
+        ));
+    }
+
+    // the same rules as on the board
+    if let Some(message) = board.blocked_move_message(col_idx, task_idx, target) {
+        return Err(message);
+    }
+    if !force && target_column.is_at_wip_limit() {
+        return Err(format!(
+            "{} is at its WIP limit of {}; use --force to move anyway",
+            target_column.name,
+            target_column.wip_limit.unwrap_or_default()
+        ));
+    }
+
+    let mut task = project.board.columns[col_idx].tasks.remove(task_idx);
+    let is_done = project.board.is_done_column(target);
+    let column = &mut project.board.columns[target];
+    task.enter_column(&column.id, is_done);
+    let output = format!("Moved '{}' to {}\n", task.title, column.name);
+    let insert_idx = insert_position.index(column.tasks.len(), task_idx);
+    column.tasks.insert(insert_idx, task);
+    column.sort_tasks();
+    Ok(output)
+}
+
+// one line per task: id, title and what else is set
+fn task_line(task: &Task) -> String {
+    let mut line = format!("  {}  {}", task.id, task.title);
+    for tag in &task.tags {
+        line.push_str(&format!("  #{}", tag));
+    }
+    if task.priority != Priority::None {
+        line.push_str(&format!("  [{}]", task.priority.label()));
+    }
+    if let Some(due) = task.due {
+        line.push_str(&format!("  due {}", due));
+    }
+    let (done, total) = task.checklist_progress();
+    if total > 0 {
+        line.push_str(&format!("  {}/{}", done, total));
+    }
+    line.push('\n');
+    line
+}
+
+fn list_tasks(project: &Project, args: &Args) -> Result<String, String> {
+    let board = &project.board;
+    let columns = match args.option("column") {
+        Some(column) => vec![find_column(board, column)?],
+        None => (0..board.columns.len()).collect(),
+    };
+    let tag = args.option("tag");
+    let keep = |task: &Task| tag.is_none_or(|tag| task.tags.iter().any(|t| t == tag));
+
+    match (args.flag("json"), args.flag("counts")) {
+        (true, true) => return Ok(json_output::counts(project, &columns, keep, dates::today())),
+        (true, false) => return Ok(json_output::board(project, &columns, keep, dates::today())),
+        _ => {}
+    }
+    let mut out = String::new();
+    for col_idx in columns {
+        let column = &board.columns[col_idx];
+        let tasks: Vec<&Task> = column.tasks.iter().filter(|task| keep(task)).collect();
+        out.push_str(&format!("{} ({})\n", column.name, tasks.len()));
+        if !args.flag("counts") {
+            for task in tasks {
+                out.push_str(&task_line(task));
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn show_task(board: &Board, col_idx: usize, task_idx: usize) -> String {
+    let task = &board.columns[col_idx].tasks[task_idx];
+    let mut out = format!("{}\n", task.title);
+    let mut field = |name: &str, value: String| {
+        out.push_str(&format!("  {:<11}{}\n", name, value));
+    };
+    field("id", task.id.clone());
+    field("column", board.columns[col_idx].name.clone());
+    if !task.tags.is_empty() {
+        field("tags", task.tags.join(", "));
+    }
+    if task.priority != Priority::None {
+        field("priority", task.priority.label().to_string());
+    }
+    if let Some(due) = task.due {
+        field("due", due.to_string());
+    }
+    for (name, time) in [
+        ("created", task.created_at),
+        ("updated", task.updated_at),
+        ("completed", task.completed_at),
+    ] {
+        if let Some(time) = time {
+            let local = time.with_timezone(&chrono::Local);
+            field(name, local.format("%Y-%m-%d %H:%M").to_string());
+        }
+    }
+    for id in &task.blocked_by {
+        let title = board
+            .get_task(id)
+            .map_or("(deleted)".to_string(), |blocker| {
+                format!("'{}'", blocker.title)
+            });
+        field("blocked by", format!("{} {}", id, title));
+    }
+    if !task.checklist.is_empty() {
+        out.push_str("  checklist\n");
+        for item in &task.checklist {
+            let mark = if item.done { "x" } else { " " };
+            out.push_str(&format!("    [{}] {}\n", mark, item.text));
+        }
+    }
+    if !task.description.is_empty() {
+        out.push('\n');
+        for line in task.description.lines() {
+            out.push_str(&format!("  {}\n", line));
+        }
+    }
+    out
+}
+
+// names with the one that opens by default starred
+fn list_projects(
+    projects: &[Project],
+    default_index: usize,
+    repo_project: Option<&str>,
+    json: bool,
+) -> String {
+    let infos: Vec<ProjectInfo> = projects
+        .iter()
+        .enumerate()
+        .map(|(i, project)| ProjectInfo {
+            id: project.id.clone(),
+            name: project.name.clone(),
+            tasks: project.count_tasks(),
+            default: i == default_index,
+            repo: Some(project.id.as_str()) == repo_project,
+        })
+        .collect();
+    if json {
+        return json_output::projects(infos);
+    }
+
+    let mut out = String::new();
+    for info in infos {
+        let marker = if info.default { "*" } else { " " };
+        let repo = if info.repo { "  [repo]" } else { "" };
+        out.push_str(&format!(
+            "{} {}  ({} tasks){}\n",
+            marker, info.name, info.tasks, repo
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn run_on(projects: &mut Vec<Project>, command: &str, list: &[&str]) -> Result<String, String> {
+        let config: Config = serde_json::from_str("{}").unwrap();
+        let args = Args::parse(
+            &args(list),
+            &[
+                "project",
+                "column",
+                "tag",
+                "priority",
+                "due",
+                "description",
+                "format",
+                "name",
+                "into",
+            ],
+            &["force", "json", "counts"],
+        )?;
+        execute(command, &args, projects, &config, None).map(|(output, _)| output)
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = Args::parse(
+            &args(&[
+                "Fix",
+                "--tag",
+                "bug",
+                "it",
+                "--tag=ui",
+                "--force",
+                "--",
+                "--literal",
+            ]),
+            &["tag"],
+            &["force"],
+        )
+        .unwrap();
+        assert_eq!(parsed.positional, vec!["Fix", "it", "--literal"]);
+        assert_eq!(parsed.all("tag"), vec!["bug", "ui"]);
+        assert!(parsed.flag("force"));
+
+        assert!(Args::parse(&args(&["--colour", "red"]), &["tag"], &[]).is_err());
+        assert!(Args::parse(&args(&["--tag"]), &["tag"], &[]).is_err());
+    }
+
+    #[test]
+    fn test_add_move_done() {
+        let mut projects = vec![
+            Project::new("Work".to_string()),
+            Project::new("Home".to_string()),
+        ];
+        let output = run_on(
+            &mut projects,
+            "add",
+            &[
+                "Fix",
+                "login",
+                "--tag",
+                "bug",
+                "--priority",
+                "high",
+                "--project",
+                "home",
+            ],
+        )
+        .unwrap();
+        assert!(output.contains("'Fix login' to To Do"), "{}", output);
+        let task = &projects[1].board.columns[0].tasks[0];
+        assert_eq!(task.tags, vec!["bug".to_string()]);
+        assert_eq!(task.priority, Priority::High);
+        let id = task.id.clone();
+
+        run_on(
+            &mut projects,
+            "move",
+            &[&id[..6], "in progress", "--project", "Home"],
+        )
+        .unwrap();
+        assert_eq!(projects[1].board.find_task(&id), Some((1, 0)));
+
+        run_on(&mut projects, "done", &["LOGIN", "--project", "Home"]).unwrap();
+        assert_eq!(projects[1].board.find_task(&id), Some((3, 0)));
+        assert!(projects[1].board.columns[3].tasks[0].completed_at.is_some());
+        assert!(run_on(&mut projects, "done", &["Fix login", "--project", "Home"]).is_err());
+    }
+
+    #[test]
+    fn test_move_respects_wip_limit() {
+        let mut projects = vec![Project::new("Work".to_string())];
+        projects[0].board.columns[1].wip_limit = Some(0);
+        run_on(&mut projects, "add", &["Card"]).unwrap();
+
+        let error = run_on(&mut projects, "move", &["Card", "in_progress"]).unwrap_err();
+        assert!(error.contains("WIP limit"), "{}", error);
+        run_on(&mut projects, "move", &["Card", "in_progress", "--force"]).unwrap();
+        assert_eq!(projects[0].board.columns[1].tasks.len(), 1);
+    }
+
+    #[test]
+    fn test_list_show_and_ambiguous_tasks() {
+        let mut projects = vec![Project::new("Work".to_string())];
+        run_on(&mut projects, "add", &["Same", "--tag", "docs"]).unwrap();
+        run_on(&mut projects, "add", &["Same", "--column", "done"]).unwrap();
+
+        let list = run_on(&mut projects, "list", &["--tag", "docs"]).unwrap();
+        assert!(list.contains("To Do (1)"), "{}", list);
+        assert!(list.contains("Done (0)"), "{}", list);
+
+        let error = run_on(&mut projects, "show", &["same"]).unwrap_err();
+        assert!(error.contains("matches 2 tasks"), "{}", error);
+        let id = projects[0].board.columns[0].tasks[0].id.clone();
+        let shown = run_on(&mut projects, "show", &[&id]).unwrap();
+        assert!(shown.contains("column     To Do"), "{}", shown);
+        assert!(shown.contains("tags       docs"), "{}", shown);
+    }
+
+    #[test]
+    fn test_json_output() {
+        let mut projects = vec![Project::new("Work".to_string())];
+        run_on(&mut projects, "add", &["Docs", "--tag", "docs"]).unwrap();
+        run_on(&mut projects, "add", &["Bug", "--tag", "bug"]).unwrap();
+
+        let counts = run_on(&mut projects, "list", &["--counts", "--tag", "docs"]).unwrap();
+        assert_eq!(counts.lines().next(), Some("To Do (1)"));
+        assert!(!counts.contains("Docs"), "{}", counts);
+
+        let list = run_on(&mut projects, "list", &["--json", "--column", "todo"]).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&list).unwrap();
+        assert_eq!(value["columns"].as_array().unwrap().len(), 1);
+        assert_eq!(value["columns"][0]["tasks"][1]["title"], "Bug");
+        assert_eq!(value["totals"]["open"], 2);
+
+        let shown = run_on(&mut projects, "show", &["docs", "--json"]).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&shown).unwrap();
+        assert_eq!(value["task"]["tags"][0], "docs");
+
+        let listed = run_on(&mut projects, "projects", &["--json"]).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&listed).unwrap();
+        assert_eq!(value["projects"][0]["default"], true);
+        assert_eq!(value["projects"][0]["tasks"], 2);
+    }
+
+    #[test]
+    fn test_export_and_import() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut projects = vec![Project::new("Work".to_string())];
+        run_on(&mut projects, "add", &["Plan", "--tag", "docs"]).unwrap();
+        let printed = run_on(&mut projects, "export", &[]).unwrap();
+        assert!(printed.contains("- [ ] Plan #docs"), "{}", printed);
+
+        let path = dir.path().join("notes.md");
+        let path = path.to_str().unwrap();
+        run_on(&mut projects, "export", &[path]).unwrap();
+        run_on(&mut projects, "import", &[path]).unwrap();
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[1].name, "notes");
+        assert_eq!(projects[1].board.columns[0].tasks[0].title, "Plan");
+
+        run_on(&mut projects, "import", &[path, "--name", "Copy"]).unwrap();
+        assert_eq!(projects[2].name, "Copy");
+        assert!(run_on(&mut projects, "export", &["--format", "pdf"]).is_err());
+    }
+
+    #[test]
+    fn test_import_csv_into_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sheet.csv");
+        std::fs::write(
+            &path,
+            "column,title,tags\nin progress,Review,\"a, b\"\nBlocked,Wait,\n",
+        )
+        .unwrap();
+        let mut projects = vec![Project::new("Work".to_string())];
+
+        let path = path.to_str().unwrap();
+        let output = run_on(&mut projects, "import", &[path, "--into", "work"]).unwrap();
+        assert_eq!(output, "Imported 2 tasks into 'Work'\n");
+        let board = &projects[0].board;
+        assert_eq!(board.columns[1].tasks[0].title, "Review");
+        assert_eq!(board.columns[1].tasks[0].tags, vec!["a", "b"]);
+        assert_eq!(board.columns[4].id, "blocked");
+        assert_eq!(board.columns[4].tasks[0].title, "Wait");
+
+        let csv = run_on(&mut projects, "export", &["--format", "csv"]).unwrap();
+        // header, two tasks and a row for each of the three empty columns
+        assert_eq!(csv.lines().count(), 6);
+    }
+
+    #[test]
+    fn test_import_detects_json_exports() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.json");
+        std::fs::write(
+            &path,
+            r#"[{"uuid": "a", "description": "Water plants", "status": "pending"}]"#,
+        )
+        .unwrap();
+        let mut projects = vec![Project::new("Work".to_string())];
+
+        let path = path.to_str().unwrap();
+        run_on(&mut projects, "import", &[path]).unwrap();
+        assert_eq!(projects[1].name, "tasks");
+        assert_eq!(projects[1].board.columns[0].tasks[0].title, "Water plants");
+        let error = run_on(&mut projects, "import", &[path, "--format", "trello"]).unwrap_err();
+        assert!(error.contains("not a valid export"), "{}", error);
+        assert!(run_on(&mut projects, "export", &["--format", "github"]).is_err());
+    }
+
+    #[test]
+    fn test_save_fails_when_changed_meanwhile() {
+        use crate::storage::{Backend, JsonBackend};
+        let dir = tempfile::tempdir().unwrap();
+        let projects_dir = dir.path().join("projects");
+        let mut backend = JsonBackend::new(projects_dir.clone());
+        backend.save(&[Project::new("Work".to_string())]).unwrap();
+
+        let mut projects = backend.load(false).unwrap();
+        let loaded = backend.stamp();
+        JsonBackend::new(projects_dir.clone())
+            .save(&[
+                Project::new("Work".to_string()),
+                Project::new("Elsewhere".to_string()),
+            ])
+            .unwrap();
+        run_on(&mut projects, "add", &["Mine"]).unwrap();
+        let error = save_unless_changed(&mut backend, loaded, &projects).unwrap_err();
+        assert!(error.contains("run it again"), "{}", error);
+        assert_eq!(JsonBackend::new(projects_dir).load(false).unwrap().len(), 2);
+
+        let loaded = backend.stamp();
+        save_unless_changed(&mut backend, loaded, &projects).unwrap();
+    }
+}
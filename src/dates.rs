@@ -0,0 +1,136 @@
+use chrono::{Datelike, Duration, Local, NaiveDate, Weekday};
+
+// tasks due within this many days (and not overdue) count as "due soon"
+pub const DUE_SOON_DAYS: i64 = 2;
+
+// today's date in local time
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// parse a due date typed by the user, relative to `today`.
+///
+/// Accepts absolute dates (`2026-11-03`), `today`, `tomorrow`, `yesterday`,
+/// weekday names (`fri`, `friday` - always the next one, never today) and
+/// offsets (`+3d`, `+2w`). An empty input clears the due date.
+pub fn parse_due_date(input: &str, today: NaiveDate) -> Result<Option<NaiveDate>, String> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return Ok(None);
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        return Ok(Some(date));
+    }
+
+    match input.as_str() {
+        "today" => return Ok(Some(today)),
+        "tomorrow" => return Ok(Some(today + Duration::days(1))),
+        "yesterday" => return Ok(Some(today - Duration::days(1))),
+        _ => {}
+    }
+
+    if let Some(weekday) = parse_weekday(&input) {
+        let current = today.weekday().num_days_from_monday() as i64;
+        let target = weekday.num_days_from_monday() as i64;
+        let mut days_ahead = (target - current).rem_euclid(7);
+        if days_ahead == 0 {
+            days_ahead = 7;
+        }
+        return Ok(Some(today + Duration::days(days_ahead)));
+    }
+
+    if let Some(offset) = input.strip_prefix('+') {
+        if let Some(unit) = offset.chars().last() {
+            let amount = offset[..offset.len() - unit.len_utf8()].parse::<i64>();
+            let days = match (amount, unit) {
+                (Ok(amount), 'd') => Some(amount),
+                (Ok(amount), 'w') => amount.checked_mul(7),
+                _ => None,
+            };
+            // checked so absurd offsets are rejected instead of overflowing
+            let date = days
+                .and_then(Duration::try_days)
+                .and_then(|offset| today.checked_add_signed(offset));
+            if date.is_some() {
+                return Ok(date);
+            }
+        }
+    }
+
+    Err(format!(
+        "Invalid due date '{}'. Use YYYY-MM-DD, today, tomorrow, a weekday (fri) or an offset (+3d, +2w).",
+        input
+    ))
+}
+
+fn parse_weekday(input: &str) -> Option<Weekday> {
+    let weekday = match input {
+        "mon" | "monday" => Weekday::Mon,
+        "tue" | "tues" | "tuesday" => Weekday::Tue,
+        "wed" | "wednesday" => Weekday::Wed,
+        "thu" | "thur" | "thurs" | "thursday" => Weekday::Thu,
+        "fri" | "friday" => Weekday::Fri,
+        "sat" | "saturday" => Weekday::Sat,
+        "sun" | "sunday" => Weekday::Sun,
+        _ => return None,
+    };
+    Some(weekday)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2026-10-14 is a Wednesday
+    fn wednesday() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 14).unwrap()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(y, m, d)
+    }
+
+    #[test]
+    fn test_absolute_date() {
+        assert_eq!(
+            parse_due_date("2026-11-03", wednesday()),
+            Ok(date(2026, 11, 3))
+        );
+    }
+
+    #[test]
+    fn test_relative_words() {
+        let today = wednesday();
+        assert_eq!(parse_due_date("today", today), Ok(Some(today)));
+        assert_eq!(parse_due_date("Tomorrow", today), Ok(date(2026, 10, 15)));
+        assert_eq!(parse_due_date("yesterday", today), Ok(date(2026, 10, 13)));
+    }
+
+    #[test]
+    fn test_weekdays() {
+        let today = wednesday();
+        assert_eq!(parse_due_date("fri", today), Ok(date(2026, 10, 16)));
+        assert_eq!(parse_due_date("monday", today), Ok(date(2026, 10, 19)));
+        // same weekday means next week
+        assert_eq!(parse_due_date("wed", today), Ok(date(2026, 10, 21)));
+    }
+
+    #[test]
+    fn test_offsets() {
+        let today = wednesday();
+        assert_eq!(parse_due_date("+3d", today), Ok(date(2026, 10, 17)));
+        assert_eq!(parse_due_date("+2w", today), Ok(date(2026, 10, 28)));
+        assert_eq!(parse_due_date("+0d", today), Ok(Some(today)));
+    }
+
+    #[test]
+    fn test_empty_clears_and_garbage_errors() {
+        assert_eq!(parse_due_date("  ", wednesday()), Ok(None));
+        assert!(parse_due_date("someday", wednesday()).is_err());
+        assert!(parse_due_date("+3x", wednesday()).is_err());
+        assert!(parse_due_date("+d", wednesday()).is_err());
+        assert!(parse_due_date("+99999999999w", wednesday()).is_err());
+        assert!(parse_due_date("2026-13-01", wednesday()).is_err());
+    }
+}
@@ -0,0 +1,620 @@
+//! Importers for boards kept in other tools, each making a new project from
+//! the tool's JSON export:
+//!
+//! - Trello: a board exported as JSON; open lists become columns.
+//! - GitHub: `gh issue list --json ...` or the REST API's issue list, where
+//!   open and closed become columns, or `gh project item-list --format json`,
+//!   where each status does, followed by Done.
+//! - Taskwarrior: `task export`; pending, started and waiting tasks go to
+//!   their own columns and completed ones to Done.
+//!
+//! Labels and tags become task tags. The last column counts as done.
+
+use crate::board::{Board, ChecklistItem, Priority, Project, Task};
+use chrono::{DateTime, Local, NaiveDateTime, Utc};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// the tool a JSON file was exported from, judging by its shape
+pub fn detect(content: &str) -> Option<&'static str> {
+    let first_line = content.trim_start().lines().next().unwrap_or_default();
+    let value: Value = match serde_json::from_str(content) {
+        Ok(value) => value,
+        // Taskwarrior before 2.6 wrote one object per line
+        Err(_) => serde_json::from_str(first_line.trim_end_matches(',')).ok()?,
+    };
+    let first = match &value {
+        Value::Array(items) => items.first()?,
+        object => object,
+    };
+    if first.get("lists").is_some() && first.get("cards").is_some() {
+        Some("trello")
+    } else if first.get("uuid").is_some() {
+        Some("taskwarrior")
+    } else if first.get("items").is_some() || first.get("number").is_some() {
+        Some("github")
+    } else {
+        None
+    }
+}
+
+// a board without columns, for the importer to fill
+fn empty_project(name: String) -> Project {
+    let mut project = Project::new(name);
+    project.board = Board {
+        columns: Vec::new(),
+        blocked_gate: None,
+    };
+    project
+}
+
+// the column with the given name, added if there is none yet
+fn column_named(board: &mut Board, name: &str) -> usize {
+    match board
+        .columns
+        .iter()
+        .position(|col| col.name.eq_ignore_ascii_case(name))
+    {
+        Some(col_idx) => col_idx,
+        None => board.add_column(name.to_string()),
+    }
+}
+
+// record each task entering its column now that the done column is known,
+// keeping completion times the other tool had
+fn enter_columns(board: &mut Board) {
+    let last = board.columns.len().saturating_sub(1);
+    for (col_idx, column) in board.columns.iter_mut().enumerate() {
+        for task in &mut column.tasks {
+            let completed = task.completed_at;
+            task.enter_column(&column.id, col_idx == last);
+            if col_idx == last {
+                task.completed_at = completed.or(task.completed_at);
+            }
+        }
+    }
+}
+
+// an RFC 3339 time; tools write empty strings or nulls for unset ones
+fn rfc3339(value: Option<&str>) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value?)
+        .ok()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+fn parse_json<'a, T: Deserialize<'a>>(content: &'a str) -> Result<T, String> {
+    serde_json::from_str(content).map_err(|e| format!("not a valid export: {}", e))
+}
+
+#[derive(Deserialize)]
+struct TrelloBoard {
+    #[serde(default)]
+    name: String,
+    lists: Vec<TrelloList>,
+    cards: Vec<TrelloCard>,
+    #[serde(default)]
+    checklists: Vec<TrelloChecklist>,
+}
+
+#[derive(Deserialize)]
+struct TrelloList {
+    id: String,
+    name: String,
+    #[serde(default)]
+    closed: bool,
+    #[serde(default)]
+    pos: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TrelloCard {
+    id: String,
+    name: String,
+    #[serde(default)]
+    desc: String,
+    id_list: String,
+    #[serde(default)]
+    closed: bool,
+    #[serde(default)]
+    pos: f64,
+    #[serde(default)]
+    due: Option<String>,
+    #[serde(default)]
+    labels: Vec<TrelloLabel>,
+}
+
+#[derive(Deserialize)]
+struct TrelloLabel {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    color: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TrelloChecklist {
+    id_card: String,
+    #[serde(default)]
+    pos: f64,
+    #[serde(default)]
+    check_items: Vec<TrelloCheckItem>,
+}
+
+#[derive(Deserialize)]
+struct TrelloCheckItem {
+    name: String,
+    state: String,
+    #[serde(default)]
+    pos: f64,
+}
+
+/// a Trello board export; archived lists and cards are left out
+pub fn trello(content: &str, name: String) -> Result<Project, String> {
+    let export: TrelloBoard = parse_json(content)?;
+    let name = if export.name.is_empty() {
+        name
+    } else {
+        export.name
+    };
+    let mut project = empty_project(name);
+
+    let mut lists: Vec<&TrelloList> = export.lists.iter().filter(|list| !list.closed).collect();
+    lists.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+    let mut column_of = HashMap::new();
+    for list in lists {
+        column_of.insert(
+            list.id.as_str(),
+            project.board.add_column(list.name.clone()),
+        );
+    }
+
+    let mut checklists: Vec<&TrelloChecklist> = export.checklists.iter().collect();
+    checklists.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+    let mut cards: Vec<&TrelloCard> = export.cards.iter().filter(|card| !card.closed).collect();
+    cards.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+    for card in cards {
+        let Some(&col_idx) = column_of.get(card.id_list.as_str()) else {
+            continue;
+        };
+        let mut task = Task::new(card.name.clone());
+        task.description = card.desc.clone();
+        task.due = rfc3339(card.due.as_deref()).map(|due| due.with_timezone(&Local).date_naive());
+        for label in &card.labels {
+            // unnamed labels only have a color
+            match (&label.name[..], &label.color) {
+                ("", Some(color)) => task.add_tag(color.clone()),
+                ("", None) => {}
+                (name, _) => task.add_tag(name.to_string()),
+            }
+        }
+        for checklist in checklists.iter().filter(|list| list.id_card == card.id) {
+            let mut items: Vec<&TrelloCheckItem> = checklist.check_items.iter().collect();
+            items.sort_by(|a, b| a.pos.total_cmp(&b.pos));
+            task.checklist
+                .extend(items.into_iter().map(|item| ChecklistItem {
+                    text: item.name.clone(),
+                    done: item.state == "complete",
+                }));
+        }
+        project.board.columns[col_idx].tasks.push(task);
+    }
+    if project.board.columns.is_empty() {
+        return Err("the board has no open lists".to_string());
+    }
+    enter_columns(&mut project.board);
+    Ok(project)
+}
+
+// labels are objects in the REST API and in `gh issue list`, but plain
+// names in `gh project item-list`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum GithubLabel {
+    Name(String),
+    Object { name: String },
+}
+
+impl GithubLabel {
+    fn name(&self) -> &str {
+        match self {
+            Self::Name(name) | Self::Object { name } => name,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GithubIssue {
+    #[serde(default)]
+    number: Option<u64>,
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    state: String,
+    #[serde(default)]
+    labels: Vec<GithubLabel>,
+    // the REST API's url is for the API, html_url for people
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    html_url: Option<String>,
+    #[serde(default, alias = "createdAt")]
+    created_at: Option<String>,
+    #[serde(default, alias = "closedAt")]
+    closed_at: Option<String>,
+    // the REST API lists pull requests among issues
+    #[serde(default)]
+    pull_request: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct GithubProject {
+    items: Vec<GithubItem>,
+}
+
+#[derive(Deserialize)]
+struct GithubItem {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    labels: Vec<GithubLabel>,
+    #[serde(default)]
+    content: Option<GithubItemContent>,
+}
+
+#[derive(Deserialize)]
+struct GithubItemContent {
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+}
+
+// the issue or item text, with a link back to it
+fn github_description(body: Option<&str>, url: Option<&str>) -> String {
+    let body = body.unwrap_or_default().trim().replace("\r\n", "\n");
+    match url {
+        Some(url) if body.is_empty() => url.to_string(),
+        Some(url) => format!("{}\n\n{}", body, url),
+        None => body,
+    }
+}
+
+/// a list of GitHub issues or the items of a GitHub project
+pub fn github(content: &str, name: String) -> Result<Project, String> {
+    let mut project = empty_project(name);
+    let board = &mut project.board;
+    if content.trim_start().starts_with('[') {
+        let issues: Vec<GithubIssue> = parse_json(content)?;
+        board.add_column("Open".to_string());
+        board.add_column("Closed".to_string());
+        for issue in issues.iter().filter(|issue| issue.pull_request.is_none()) {
+            let title = match issue.number {
+                Some(number) => format!("#{} {}", number, issue.title),
+                None => issue.title.clone(),
+            };
+            let mut task = Task::new(title);
+            let url = issue.html_url.as_ref().or(issue.url.as_ref());
+            task.description = github_description(issue.body.as_deref(), url.map(String::as_str));
+            for label in &issue.labels {
+                task.add_tag(label.name().to_string());
+            }
+            task.created_at = rfc3339(issue.created_at.as_deref()).or(task.created_at);
+            task.completed_at = rfc3339(issue.closed_at.as_deref());
+            let col_idx = if issue.state.eq_ignore_ascii_case("closed") {
+                1
+            } else {
+                0
+            };
+            board.columns[col_idx].tasks.push(task);
+        }
+    } else {
+        let export: GithubProject = parse_json(content)?;
+        for item in &export.items {
+            let status = item.status.as_deref().unwrap_or("No Status");
+            let col_idx = column_named(board, status);
+            let mut task = Task::new(item.title.clone());
+            let content = item.content.as_ref();
+            task.description = github_description(
+                content.and_then(|c| c.body.as_deref()),
+                content.and_then(|c| c.url.as_deref()),
+            );
+            for label in &item.labels {
+                task.add_tag(label.name().to_string());
+            }
+            board.columns[col_idx].tasks.push(task);
+        }
+        if board.columns.is_empty() {
+            board.add_column("No Status".to_string());
+        }
+        // statuses come in the order items use them: Done goes last, even
+        // when no item has it, so no other status counts as done
+        let done = column_named(board, "Done");
+        let column = board.columns.remove(done);
+        board.columns.push(column);
+    }
+    enter_columns(board);
+    Ok(project)
+}
+
+#[derive(Deserialize)]
+struct TaskwarriorTask {
+    uuid: String,
+    description: String,
+    status: String,
+    #[serde(default)]
+    entry: Option<String>,
+    #[serde(default)]
+    modified: Option<String>,
+    #[serde(default)]
+    end: Option<String>,
+    #[serde(default)]
+    start: Option<String>,
+    #[serde(default)]
+    due: Option<String>,
+    #[serde(default)]
+    priority: Option<String>,
+    #[serde(default)]
+    project: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    annotations: Vec<TaskwarriorAnnotation>,
+    // a comma separated string before 2.6, a list since
+    #[serde(default)]
+    depends: Option<Value>,
+}
+
+#[derive(Deserialize)]
+struct TaskwarriorAnnotation {
+    description: String,
+}
+
+// Taskwarrior's compact ISO 8601 times, like 20261018T093000Z
+fn taskwarrior_time(value: Option<&str>) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value?, "%Y%m%dT%H%M%SZ")
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+/// the output of `task export`; deleted tasks are left out
+pub fn taskwarrior(content: &str, name: String) -> Result<Project, String> {
+    let tasks: Vec<TaskwarriorTask> = if content.trim_start().starts_with('[') {
+        parse_json(content)?
+    } else {
+        content
+            .lines()
+            .map(|line| line.trim().trim_end_matches(','))
+            .filter(|line| !line.is_empty())
+            .map(parse_json)
+            .collect::<Result<_, _>>()?
+    };
+
+    let mut project = empty_project(name);
+    let board = &mut project.board;
+    for column in ["To Do", "In Progress", "Waiting", "Done"] {
+        board.add_column(column.to_string());
+    }
+    let mut ids = HashMap::new();
+    let mut depends = Vec::new();
+    for tw in tasks.iter().filter(|tw| tw.status != "deleted") {
+        let mut task = Task::new(tw.description.clone());
+        if let Some(project) = &tw.project {
+            task.add_tag(project.clone());
+        }
+        for tag in &tw.tags {
+            task.add_tag(tag.clone());
+        }
+        task.priority = match tw.priority.as_deref() {
+            Some("H") => Priority::High,
+            Some("M") => Priority::Medium,
+            Some("L") => Priority::Low,
+            _ => Priority::None,
+        };
+        // the due time is midnight local time, stored in UTC
+        task.due =
+            taskwarrior_time(tw.due.as_deref()).map(|due| due.with_timezone(&Local).date_naive());
+        let notes: Vec<&str> = tw
+            .annotations
+            .iter()
+            .map(|note| note.description.as_str())
+            .collect();
+        task.description = notes.join("\n");
+        task.created_at = taskwarrior_time(tw.entry.as_deref()).or(task.created_at);
+        task.updated_at = taskwarrior_time(tw.modified.as_deref()).or(task.updated_at);
+        task.completed_at = taskwarrior_time(tw.end.as_deref());
+
+        let col_idx = match tw.status.as_str() {
+            "completed" => 3,
+            "waiting" => 2,
+            _ if tw.start.is_some() => 1,
+            _ => 0,
+        };
+        ids.insert(tw.uuid.as_str(), task.id.clone());
+        depends.push((task.id.clone(), tw.depends.as_ref()));
+        board.columns[col_idx].tasks.push(task);
+    }
+
+    // dependencies become blockers, once every task has its new id
+    for (id, depends) in depends {
+        let uuids: Vec<&str> = match depends {
+            Some(Value::String(list)) => list.split(',').collect(),
+            Some(Value::Array(list)) => list.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        let blockers: Vec<String> = uuids
+            .iter()
+            .filter_map(|uuid| ids.get(uuid.trim()).cloned())
+            .collect();
+        if let Some(task) = board.get_task_mut(&id) {
+            task.blocked_by = blockers;
+        }
+    }
+    // Waiting is only kept when something waits
+    if board.columns[2].tasks.is_empty() {
+        board.columns.remove(2);
+    }
+    enter_columns(board);
+    Ok(project)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_trello() {
+        let content = r#"{
+            "name": "Roadmap",
+            "lists": [
+                {"id": "l2", "name": "Doing", "closed": false, "pos": 2},
+                {"id": "l1", "name": "Backlog", "closed": false, "pos": 1},
+                {"id": "l3", "name": "Old", "closed": true, "pos": 3}
+            ],
+            "cards": [
+                {"id": "c1", "name": "Search", "desc": "full text", "idList": "l1", "closed": false,
+                 "pos": 2, "due": "2026-10-20T10:00:00.000Z",
+                 "labels": [{"name": "feature", "color": "green"}, {"name": "", "color": "red"}]},
+                {"id": "c2", "name": "Login", "idList": "l1", "closed": false, "pos": 1, "due": null, "labels": []},
+                {"id": "c3", "name": "Gone", "idList": "l2", "closed": true, "pos": 1, "labels": []}
+            ],
+            "checklists": [
+                {"id": "k1", "idCard": "c1", "pos": 1, "checkItems": [
+                    {"name": "index", "state": "complete", "pos": 1},
+                    {"name": "ui", "state": "incomplete", "pos": 2}
+                ]}
+            ]
+        }"#;
+        assert_eq!(detect(content), Some("trello"));
+        let project = trello(content, "file".to_string()).unwrap();
+        assert_eq!(project.name, "Roadmap");
+        let names: Vec<&str> = project
+            .board
+            .columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Backlog", "Doing"]);
+        let titles: Vec<&str> = project.board.columns[0]
+            .tasks
+            .iter()
+            .map(|t| t.title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["Login", "Search"]);
+        let search = &project.board.columns[0].tasks[1];
+        assert_eq!(search.tags, vec!["feature", "red"]);
+        assert!(search.due.is_some());
+        assert_eq!(search.checklist_progress(), (1, 2));
+        assert!(project.board.columns[1].tasks.is_empty());
+    }
+
+    #[test]
+    fn test_github_issues_and_project() {
+        let issues = r#"[
+            {"number": 7, "title": "Crash", "body": "trace", "state": "OPEN",
+             "labels": [{"name": "bug"}], "url": "https://github.com/o/r/issues/7",
+             "createdAt": "2026-10-01T09:00:00Z", "closedAt": null},
+            {"number": 5, "title": "Docs", "state": "closed", "labels": [],
+             "url": "https://api.github.com/repos/o/r/issues/5",
+             "html_url": "https://github.com/o/r/issues/5", "closed_at": "2026-10-02T09:00:00Z"},
+            {"number": 6, "title": "A pull request", "state": "open", "pull_request": {}}
+        ]"#;
+        assert_eq!(detect(issues), Some("github"));
+        let project = github(issues, "issues".to_string()).unwrap();
+        let board = &project.board;
+        assert_eq!(board.columns[0].tasks.len(), 1);
+        let crash = &board.columns[0].tasks[0];
+        assert_eq!(crash.title, "#7 Crash");
+        assert_eq!(crash.tags, vec!["bug"]);
+        assert_eq!(
+            crash.description,
+            "trace\n\nhttps://github.com/o/r/issues/7"
+        );
+        let docs = &board.columns[1].tasks[0];
+        assert_eq!(docs.description, "https://github.com/o/r/issues/5");
+        assert_eq!(
+            docs.completed_at.map(|t| t.date_naive()),
+            NaiveDate::from_ymd_opt(2026, 10, 2)
+        );
+
+        let items = r#"{"items": [
+            {"title": "Ship", "status": "Done", "labels": ["release"]},
+            {"title": "Design", "status": "In Progress", "content": {"body": "mockups"}},
+            {"title": "Idea"}
+        ], "totalCount": 3}"#;
+        assert_eq!(detect(items), Some("github"));
+        let project = github(items, "project".to_string()).unwrap();
+        let names: Vec<&str> = project
+            .board
+            .columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["In Progress", "No Status", "Done"]);
+        assert_eq!(project.board.columns[2].tasks[0].tags, vec!["release"]);
+        assert!(project.board.columns[2].tasks[0].completed_at.is_some());
+
+        // without a Done status, an empty Done column is added last
+        let items = r#"{"items": [
+            {"title": "Design", "status": "Todo"},
+            {"title": "Review", "status": "Shipped?"}
+        ]}"#;
+        let project = github(items, "project".to_string()).unwrap();
+        let names: Vec<&str> = project
+            .board
+            .columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Todo", "Shipped?", "Done"]);
+        assert!(project.board.columns[2].tasks.is_empty());
+        assert!(project.board.columns[1].tasks[0].completed_at.is_none());
+    }
+
+    #[test]
+    fn test_taskwarrior() {
+        let content = r#"[
+            {"id": 1, "uuid": "a-1", "description": "Write tests", "status": "pending",
+             "entry": "20261001T080000Z", "priority": "H", "project": "crate", "tags": ["dev"],
+             "depends": "b-2", "annotations": [{"entry": "20261002T080000Z", "description": "unit first"}]},
+            {"id": 2, "uuid": "b-2", "description": "Refactor", "status": "pending",
+             "entry": "20261001T080000Z", "start": "20261003T080000Z"},
+            {"id": 0, "uuid": "c-3", "description": "Plan", "status": "completed",
+             "entry": "20260901T080000Z", "end": "20260902T080000Z"},
+            {"id": 0, "uuid": "d-4", "description": "Dropped", "status": "deleted"}
+        ]"#;
+        assert_eq!(detect(content), Some("taskwarrior"));
+        let project = taskwarrior(content, "tasks".to_string()).unwrap();
+        let board = &project.board;
+        let names: Vec<&str> = board.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["To Do", "In Progress", "Done"]);
+
+        let write = &board.columns[0].tasks[0];
+        assert_eq!(write.priority, Priority::High);
+        assert_eq!(write.tags, vec!["crate", "dev"]);
+        assert_eq!(write.description, "unit first");
+        let refactor = &board.columns[1].tasks[0];
+        assert_eq!(write.blocked_by, vec![refactor.id.clone()]);
+        assert!(board.is_blocked(write));
+        let plan = &board.columns[2].tasks[0];
+        assert_eq!(
+            plan.completed_at.map(|t| t.date_naive()),
+            NaiveDate::from_ymd_opt(2026, 9, 2)
+        );
+
+        // one object per line, as older versions export
+        let lines = "{\"uuid\": \"a\", \"description\": \"One\", \"status\": \"waiting\"},\n{\"uuid\": \"b\", \"description\": \"Two\", \"status\": \"pending\"}\n";
+        assert_eq!(detect(lines), Some("taskwarrior"));
+        let project = taskwarrior(lines, "tasks".to_string()).unwrap();
+        assert_eq!(project.board.columns[2].name, "Waiting");
+        assert_eq!(project.board.columns[2].tasks[0].title, "One");
+
+        assert_eq!(detect("## To Do\n"), None);
+    }
+}
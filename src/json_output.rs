@@ -0,0 +1,350 @@
+//! The JSON printed by `--json`, for scripts and status bars.
+//!
+//! This is a contract, separate from how projects are stored: fields may be
+//! added, but none are renamed or removed without bumping `VERSION`. The
+//! schema is documented in the README under "JSON Output".
+
+use crate::board::{Board, DueStatus, Project, Task};
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use serde::Serialize;
+
+pub const VERSION: u32 = 1;
+
+#[derive(Serialize)]
+pub struct ProjectInfo {
+    pub id: String,
+    pub name: String,
+    pub tasks: usize,
+    // the project commands use without --project
+    pub default: bool,
+    // the board committed to the current repository
+    pub repo: bool,
+}
+
+#[derive(Serialize)]
+struct Projects {
+    version: u32,
+    projects: Vec<ProjectInfo>,
+}
+
+#[derive(Serialize)]
+struct ProjectRef {
+    id: String,
+    name: String,
+}
+
+// summed over the listed columns; `open` leaves out the done column
+#[derive(Serialize, Default)]
+struct Totals {
+    tasks: usize,
+    open: usize,
+    overdue: usize,
+    due_soon: usize,
+    blocked: usize,
+}
+
+#[derive(Serialize)]
+struct ColumnCounts {
+    id: String,
+    name: String,
+    done: bool,
+    wip_limit: Option<usize>,
+    count: usize,
+    overdue: usize,
+    due_soon: usize,
+    blocked: usize,
+}
+
+#[derive(Serialize)]
+struct Counts {
+    version: u32,
+    project: ProjectRef,
+    totals: Totals,
+    columns: Vec<ColumnCounts>,
+}
+
+#[derive(Serialize)]
+struct Checklist {
+    done: usize,
+    total: usize,
+}
+
+#[derive(Serialize)]
+struct TaskOutput {
+    id: String,
+    title: String,
+    column: String,
+    tags: Vec<String>,
+    priority: &'static str,
+    due: Option<String>,
+    // "overdue", "due_soon", "later", or null without a due date or when done
+    due_status: Option<&'static str>,
+    blocked: bool,
+    blocked_by: Vec<String>,
+    checklist: Checklist,
+    description: String,
+    created: Option<String>,
+    updated: Option<String>,
+    completed: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ColumnOutput {
+    id: String,
+    name: String,
+    done: bool,
+    wip_limit: Option<usize>,
+    tasks: Vec<TaskOutput>,
+}
+
+#[derive(Serialize)]
+struct BoardOutput {
+    version: u32,
+    project: ProjectRef,
+    totals: Totals,
+    columns: Vec<ColumnOutput>,
+}
+
+#[derive(Serialize)]
+struct SingleTask {
+    version: u32,
+    project: ProjectRef,
+    task: TaskOutput,
+}
+
+fn to_string<T: Serialize>(value: &T) -> String {
+    // only strings, numbers and options: serializing can't fail
+    let mut out = serde_json::to_string_pretty(value).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+fn timestamp(time: Option<DateTime<Utc>>) -> Option<String> {
+    time.map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+fn project_ref(project: &Project) -> ProjectRef {
+    ProjectRef {
+        id: project.id.clone(),
+        name: project.name.clone(),
+    }
+}
+
+// due dates don't matter any more once a task is done
+fn due_status(board: &Board, col_idx: usize, task: &Task, today: NaiveDate) -> DueStatus {
+    if board.is_done_column(col_idx) {
+        DueStatus::NoDueDate
+    } else {
+        task.due_status(today)
+    }
+}
+
+fn count_column(
+    board: &Board,
+    col_idx: usize,
+    keep: &impl Fn(&Task) -> bool,
+    today: NaiveDate,
+) -> ColumnCounts {
+    let column = &board.columns[col_idx];
+    let tasks: Vec<&Task> = column.tasks.iter().filter(|task| keep(task)).collect();
+    let status = |wanted| {
+        tasks
+            .iter()
+            .filter(|task| due_status(board, col_idx, task, today) == wanted)
+            .count()
+    };
+    ColumnCounts {
+        id: column.id.clone(),
+        name: column.name.clone(),
+        done: board.is_done_column(col_idx),
+        wip_limit: column.wip_limit,
+        count: tasks.len(),
+        overdue: status(DueStatus::Overdue),
+        due_soon: status(DueStatus::DueSoon),
+        blocked: tasks
+            .iter()
+            .filter(|task| !board.is_done_column(col_idx) && board.is_blocked(task))
+            .count(),
+    }
+}
+
+fn totals(columns: &[ColumnCounts]) -> Totals {
+    let mut totals = Totals::default();
+    for column in columns {
+        totals.tasks += column.count;
+        if !column.done {
+            totals.open += column.count;
+        }
+        totals.overdue += column.overdue;
+        totals.due_soon += column.due_soon;
+        totals.blocked += column.blocked;
+    }
+    totals
+}
+
+fn task_output(board: &Board, col_idx: usize, task: &Task, today: NaiveDate) -> TaskOutput {
+    let column = &board.columns[col_idx];
+    let (done, total) = task.checklist_progress();
+    TaskOutput {
+        id: task.id.clone(),
+        title: task.title.clone(),
+        column: column.id.clone(),
+        tags: task.tags.clone(),
+        priority: task.priority.label(),
+        due: task.due.map(|due| due.to_string()),
+        due_status: match due_status(board, col_idx, task, today) {
+            DueStatus::NoDueDate => None,
+            DueStatus::Overdue => Some("overdue"),
+            DueStatus::DueSoon => Some("due_soon"),
+            DueStatus::Later => Some("later"),
+        },
+        blocked: !board.is_done_column(col_idx) && board.is_blocked(task),
+        blocked_by: task.blocked_by.clone(),
+        checklist: Checklist { done, total },
+        description: task.description.clone(),
+        created: timestamp(task.created_at),
+        updated: timestamp(task.updated_at),
+        completed: timestamp(task.completed_at),
+    }
+}
+
+/// the project list
+pub fn projects(projects: Vec<ProjectInfo>) -> String {
+    to_string(&Projects {
+        version: VERSION,
+        projects,
+    })
+}
+
+/// per-column counts of the tasks `keep` picks, for status bars
+pub fn counts(
+    project: &Project,
+    columns: &[usize],
+    keep: impl Fn(&Task) -> bool,
+    today: NaiveDate,
+) -> String {
+    let columns: Vec<ColumnCounts> = columns
+        .iter()
+        .map(|&col_idx| count_column(&project.board, col_idx, &keep, today))
+        .collect();
+    to_string(&Counts {
+        version: VERSION,
+        project: project_ref(project),
+        totals: totals(&columns),
+        columns,
+    })
+}
+
+/// the columns with their tasks; `keep` picks the tasks to include
+pub fn board(
+    project: &Project,
+    columns: &[usize],
+    keep: impl Fn(&Task) -> bool,
+    today: NaiveDate,
+) -> String {
+    let board = &project.board;
+    let counts: Vec<ColumnCounts> = columns
+        .iter()
+        .map(|&col_idx| count_column(board, col_idx, &keep, today))
+        .collect();
+    let columns = columns
+        .iter()
+        .map(|&col_idx| {
+            let column = &board.columns[col_idx];
+            ColumnOutput {
+                id: column.id.clone(),
+                name: column.name.clone(),
+                done: board.is_done_column(col_idx),
+                wip_limit: column.wip_limit,
+                tasks: column
+                    .tasks
+                    .iter()
+                    .filter(|task| keep(task))
+                    .map(|task| task_output(board, col_idx, task, today))
+                    .collect(),
+            }
+        })
+        .collect();
+    to_string(&BoardOutput {
+        version: VERSION,
+        project: project_ref(project),
+        totals: totals(&counts),
+        columns,
+    })
+}
+
+/// a single task
+pub fn task(project: &Project, col_idx: usize, task_idx: usize, today: NaiveDate) -> String {
+    let board = &project.board;
+    let task = &board.columns[col_idx].tasks[task_idx];
+    to_string(&SingleTask {
+        version: VERSION,
+        project: project_ref(project),
+        task: task_output(board, col_idx, task, today),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Priority;
+    use chrono::Duration;
+    use serde_json::Value;
+
+    #[test]
+    fn test_counts() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let mut project = Project::new("Work".to_string());
+        let mut late = Task::new("Late".to_string());
+        late.due = Some(today - Duration::days(1));
+        let mut soon = Task::new("Soon".to_string());
+        soon.due = Some(today + Duration::days(1));
+        soon.blocked_by.push(late.id.clone());
+        let mut finished = Task::new("Finished".to_string());
+        finished.due = Some(today - Duration::days(3));
+        project.board.columns[0].tasks.push(late);
+        project.board.columns[1].tasks.push(soon);
+        project.board.columns[3].tasks.push(finished);
+
+        let value: Value =
+            serde_json::from_str(&counts(&project, &[0, 1, 2, 3], |_| true, today)).unwrap();
+        assert_eq!(value["version"], VERSION);
+        assert_eq!(value["project"]["name"], "Work");
+        assert_eq!(value["totals"]["tasks"], 3);
+        assert_eq!(value["totals"]["open"], 2);
+        // the done task is past its date, but that no longer matters
+        assert_eq!(value["totals"]["overdue"], 1);
+        assert_eq!(value["totals"]["due_soon"], 1);
+        assert_eq!(value["totals"]["blocked"], 1);
+        assert_eq!(value["columns"][1]["id"], "in_progress");
+        assert_eq!(value["columns"][1]["count"], 1);
+        assert_eq!(value["columns"][3]["done"], true);
+    }
+
+    #[test]
+    fn test_board_and_task() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+        let mut project = Project::new("Work".to_string());
+        let mut ship = Task::new("Ship it".to_string());
+        ship.priority = Priority::Urgent;
+        ship.due = Some(today);
+        ship.add_tag("release".to_string());
+        project.board.columns[2].tasks.push(ship);
+
+        let value: Value = serde_json::from_str(&board(&project, &[2], |_| true, today)).unwrap();
+        let columns = value["columns"].as_array().unwrap();
+        assert_eq!(columns.len(), 1);
+        let output = &columns[0]["tasks"][0];
+        assert_eq!(output["title"], "Ship it");
+        assert_eq!(output["column"], "testing");
+        assert_eq!(output["priority"], "urgent");
+        assert_eq!(output["due"], "2026-10-18");
+        assert_eq!(output["due_status"], "due_soon");
+        assert_eq!(output["tags"][0], "release");
+        assert_eq!(output["checklist"]["total"], 0);
+
+        let value: Value = serde_json::from_str(&task(&project, 2, 0, today)).unwrap();
+        assert_eq!(value["task"]["title"], "Ship it");
+        assert_eq!(value["task"]["completed"], Value::Null);
+    }
+}
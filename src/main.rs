@@ -1,7 +1,20 @@
 mod app;
 mod board;
+mod board_csv;
+mod board_text;
+mod cli;
+mod dates;
+mod importers;
+mod json_output;
+mod markdown;
+mod merge;
+mod repo_board;
+mod schema;
+#[cfg(feature = "sqlite")]
+mod sqlite;
 mod storage;
 mod theme;
+mod todo_txt;
 mod ui;
 
 use app::{App, InputMode};
@@ -12,8 +25,46 @@ use crossterm::{
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+// how often to check the project files for changes made elsewhere
+const FILE_CHECK_INTERVAL: Duration = Duration::from_millis(500);
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    // Keep config and data in another folder, e.g. a synced one
+    if let Some(i) = args.iter().position(|arg| arg == "--data-dir") {
+        let Some(dir) = args.get(i + 1) else {
+            return Err("--data-dir needs a directory".into());
+        };
+        storage::set_home(PathBuf::from(dir));
+        args.drain(i..=i + 1);
+    }
+    storage::migrate_data_dir();
+    match args.first().map(String::as_str) {
+        Some("--restore-backup") => return restore_backup(args.get(1).map(String::as_str)),
+        Some("--init-board") => return init_board(),
+        Some("merge") => return merge_boards(&args[1..]),
+        Some("--help" | "-h" | "help") => {
+            print!("{}", cli::USAGE);
+            return Ok(());
+        }
+        // Headless commands for scripts: no terminal UI
+        Some(command) if cli::is_command(command) => {
+            if let Err(e) = cli::run(command, &args[1..]) {
+                eprintln!("tui-kanban {}: {}", command, e);
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(other) => {
+            eprint!("tui-kanban: unknown command '{}'\n\n{}", other, cli::USAGE);
+            std::process::exit(2);
+        }
+        None => {}
+    }
+
     // setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -41,15 +92,73 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+// list backups, or restore the chosen one
+fn restore_backup(selector: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(selector) = selector else {
+        let backups = storage::list_backups();
+        if backups.is_empty() {
+            println!("No backups in {}", storage::get_backup_dir().display());
+        }
+        for (i, backup) in backups.iter().enumerate() {
+            println!("{:>3}  {}", i + 1, backup.display());
+        }
+        if !backups.is_empty() {
+            println!("\nRestore one with: tui-kanban --restore-backup <number>");
+        }
+        return Ok(());
+    };
+
+    let mut backend = storage::open_backend(&storage::load_config())?;
+    let backup = storage::restore_backup(backend.as_mut(), selector)?;
+    println!("Restored {}", backup.display());
+    Ok(())
+}
+
+// create a board for the repository in the current directory
+fn init_board() -> Result<(), Box<dyn std::error::Error>> {
+    let path = repo_board::init_repo_board(&std::env::current_dir()?)?;
+    println!("Created {}", path.display());
+    println!(
+        "Commit it to share the board; tui-kanban opens it when run in this directory or below."
+    );
+    println!("To let git merge it card by card, run once per clone:");
+    println!("  git config merge.tui-kanban.driver 'tui-kanban merge %O %A %B'");
+    Ok(())
+}
+
+// git merge driver: merge a board card by card into the `ours` file
+fn merge_boards(paths: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let [base, ours, theirs] = paths else {
+        return Err("usage: tui-kanban merge <base> <ours> <theirs>".into());
+    };
+    let conflicts = repo_board::merge_files(base.as_ref(), ours.as_ref(), theirs.as_ref())?;
+    if conflicts.is_empty() {
+        return Ok(());
+    }
+    // Leave the file marked as conflicted so the result gets a look before it's committed
+    for conflict in &conflicts {
+        eprintln!("tui-kanban merge: {}", conflict);
+    }
+    std::process::exit(1);
+}
+
 // main loop
 fn run_app<B: ratatui::backend::Backend + std::io::Write>(
     terminal: &mut Terminal<B>,
     app: &mut App,
 ) -> io::Result<()> {
     loop {
+        // pick up changes other instances or scripts made to the project files
+        app.check_for_external_changes();
+
         // draw UI
         terminal.draw(|f| ui::draw(f, app))?;
 
+        // wake up now and then to look at the file even without input
+        if !event::poll(FILE_CHECK_INTERVAL)? {
+            continue;
+        }
+
         // handle input
         if let Event::Key(key) = event::read()? {
             // Handle Ctrl+p globally to open project list
@@ -60,6 +169,22 @@ fn run_app<B: ratatui::backend::Backend + std::io::Write>(
                 continue;
             }
 
+            // Ctrl+r redoes the last undone action
+            if key.code == KeyCode::Char('r') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                if app.input_mode == InputMode::Normal {
+                    app.redo();
+                }
+                continue;
+            }
+
+            // Ctrl+s saves now, also enabling saving after the projects failed to load
+            if key.code == KeyCode::Char('s') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                if app.input_mode == InputMode::Normal {
+                    app.force_save();
+                }
+                continue;
+            }
+
             // Handle Ctrl+t globally to open theme selector
             if key.code == KeyCode::Char('t') && key.modifiers.contains(KeyModifiers::CONTROL) {
                 if app.input_mode == InputMode::Normal {
@@ -68,12 +193,13 @@ fn run_app<B: ratatui::backend::Backend + std::io::Write>(
                 continue;
             }
 
-            if app.input_mode.has_open_input() {
-                if key.code == KeyCode::Char('e') && key.modifiers.contains(KeyModifiers::CONTROL) {
-                    // We do not need the return value here
-                    let _ = execute_external(terminal, || app.open_external_editor());
-                    continue;
-                }
+            if app.input_mode.has_open_input()
+                && key.code == KeyCode::Char('e')
+                && key.modifiers.contains(KeyModifiers::CONTROL)
+            {
+                // We do not need the return value here
+                let _ = execute_external(terminal, || app.open_external_editor());
+                continue;
             }
 
             match app.input_mode {
@@ -81,14 +207,21 @@ fn run_app<B: ratatui::backend::Backend + std::io::Write>(
                 InputMode::AddingTask
                 | InputMode::AddingTag
                 | InputMode::AddingColumn
-                | InputMode::RenamingColumn => handle_input_mode(app, key.code),
+                | InputMode::RenamingColumn
+                | InputMode::SettingWipLimit => handle_input_mode(app, key.code),
                 InputMode::ViewingTask => handle_viewing_task_mode(app, key.code),
                 InputMode::EditingTitle => handle_editing_title_mode(app, key.code),
+                InputMode::EditingDueDate => handle_editing_due_date_mode(app, key.code),
+                InputMode::AddingChecklistItem | InputMode::AddingBlocker => {
+                    handle_detail_input_mode(app, key.code)
+                }
                 InputMode::EditingDescription => handle_editing_description_mode(app, key.code),
                 InputMode::ViewingHelp => handle_viewing_help_mode(app, key.code),
                 InputMode::ProjectList => handle_project_list_mode(app, key.code),
                 InputMode::AddingProject => handle_adding_project_mode(app, key.code),
                 InputMode::ConfirmingDelete => handle_confirming_delete_mode(app, key.code),
+                InputMode::ConfirmingWipLimit => handle_confirming_wip_limit_mode(app, key.code),
+                InputMode::ConfirmingReload => handle_confirming_reload_mode(app, key.code),
                 InputMode::SelectingTheme => handle_theme_selector_mode(app, key.code),
                 InputMode::ShowErrorInfo => handle_error_info_mode(app, key.code),
             }
@@ -103,7 +236,13 @@ fn run_app<B: ratatui::backend::Backend + std::io::Write>(
 
 // handle keys in normal mode
 fn handle_normal_mode(app: &mut App, key: KeyCode) {
+    // the footer message only lasts until the next key
+    app.status_message = None;
+
     match key {
+        // Undo (redo is Ctrl+r)
+        KeyCode::Char('u') => app.undo(),
+
         // Quit
         KeyCode::Char('q') => app.should_quit = true,
 
@@ -149,6 +288,14 @@ fn handle_normal_mode(app: &mut App, key: KeyCode) {
         KeyCode::Char('C') => app.start_adding_column(),
         KeyCode::Char('R') => app.start_renaming_column(),
         KeyCode::Char('D') => app.delete_column(),
+        KeyCode::Char('S') => app.toggle_column_sort(),
+        KeyCode::Char('G') => app.toggle_blocked_gate(),
+        KeyCode::Char('W') => app.start_setting_wip_limit(),
+
+        // Task ordering (Shift+J/K)
+        KeyCode::Char('J') => app.move_task_down(),
+        KeyCode::Char('K') => app.move_task_up(),
+        KeyCode::Char('i') => app.cycle_insert_position(),
 
         // Actions
         KeyCode::Enter => app.open_task(),
@@ -157,6 +304,8 @@ fn handle_normal_mode(app: &mut App, key: KeyCode) {
         KeyCode::Char('m') => app.move_task_forward(),
         KeyCode::Char('n') => app.move_task_backward(),
         KeyCode::Char('d') => app.delete_task(),
+        KeyCode::Char('+') => app.raise_priority(),
+        KeyCode::Char('-') => app.lower_priority(),
         KeyCode::Char('?') => app.show_help(),
 
         _ => {}
@@ -178,6 +327,14 @@ fn handle_input_mode(app: &mut App, key: KeyCode) {
 fn handle_viewing_task_mode(app: &mut App, key: KeyCode) {
     use app::TaskField;
 
+    // checklist keys take precedence while the checklist is focused
+    if app.focused_field == TaskField::Checklist && handle_checklist_keys(app, key) {
+        return;
+    }
+    if app.focused_field == TaskField::Dependencies && handle_dependency_keys(app, key) {
+        return;
+    }
+
     match key {
         KeyCode::Esc => app.close_view(),
         KeyCode::Tab | KeyCode::Char('j') => app.next_field(),
@@ -186,21 +343,49 @@ fn handle_viewing_task_mode(app: &mut App, key: KeyCode) {
             // Start editing based on focused field
             match app.focused_field {
                 TaskField::Title => app.start_editing_title(),
+                TaskField::DueDate => app.start_editing_due_date(),
                 TaskField::Description => app.start_editing_description(),
                 TaskField::Tags => {} // No action for tags, use numbers instead
+                TaskField::Checklist | TaskField::Dependencies => {} // Handled above
             }
         }
-        KeyCode::Char(c @ '1'..='9') => {
+        KeyCode::Char(c @ '1'..='9') if app.focused_field == TaskField::Tags => {
             // Remove tag by number (only when focused on tags)
-            if app.focused_field == TaskField::Tags {
-                let tag_index = (c as u8 - b'1') as usize;
-                app.remove_tag(tag_index);
-            }
+            let tag_index = (c as u8 - b'1') as usize;
+            app.remove_tag(tag_index);
         }
         _ => {}
     }
 }
 
+// handle checklist keys in task detail view, returns false if the key isn't a checklist key
+fn handle_checklist_keys(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Char('j') | KeyCode::Down => app.next_checklist_item(),
+        KeyCode::Char('k') | KeyCode::Up => app.previous_checklist_item(),
+        KeyCode::Char('J') => app.move_checklist_item(true),
+        KeyCode::Char('K') => app.move_checklist_item(false),
+        KeyCode::Char('a') => app.start_adding_checklist_item(),
+        KeyCode::Char('d') => app.delete_checklist_item(),
+        KeyCode::Char(' ') | KeyCode::Enter => app.toggle_checklist_item(),
+        _ => return false,
+    }
+    true
+}
+
+// handle dependency keys in task detail view, returns false if the key isn't a dependency key
+fn handle_dependency_keys(app: &mut App, key: KeyCode) -> bool {
+    match key {
+        KeyCode::Char('j') | KeyCode::Down => app.next_link(),
+        KeyCode::Char('k') | KeyCode::Up => app.previous_link(),
+        KeyCode::Char('a') => app.start_adding_blocker(),
+        KeyCode::Char('d') => app.remove_selected_link(),
+        KeyCode::Enter => app.open_selected_link(),
+        _ => return false,
+    }
+    true
+}
+
 // handle keys when editing title
 fn handle_editing_title_mode(app: &mut App, key: KeyCode) {
     match key {
@@ -219,6 +404,36 @@ fn handle_editing_title_mode(app: &mut App, key: KeyCode) {
     }
 }
 
+// handle keys when editing due date
+fn handle_editing_due_date_mode(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Enter => app.submit_input(),
+        KeyCode::Esc => {
+            // Cancel editing and go back to viewing task
+            app.input_mode = InputMode::ViewingTask;
+            app.input_buffer.clear();
+        }
+        KeyCode::Backspace => app.input_backspace(),
+        KeyCode::Char(c) => app.input_char(c),
+        _ => {}
+    }
+}
+
+// handle keys when adding a checklist item or blocker in task detail view
+fn handle_detail_input_mode(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Enter => app.submit_input(),
+        KeyCode::Esc => {
+            // Cancel and go back to viewing task
+            app.input_mode = InputMode::ViewingTask;
+            app.input_buffer.clear();
+        }
+        KeyCode::Backspace => app.input_backspace(),
+        KeyCode::Char(c) => app.input_char(c),
+        _ => {}
+    }
+}
+
 // handle keys when editing description
 fn handle_editing_description_mode(app: &mut App, key: KeyCode) {
     match key {
@@ -281,6 +496,25 @@ fn handle_confirming_delete_mode(app: &mut App, key: KeyCode) {
     }
 }
 
+// handle keys when confirming a move over a column's WIP limit
+fn handle_confirming_wip_limit_mode(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Char('y') | KeyCode::Char('Y') => app.confirm_pending_move(),
+        KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => app.cancel_pending_move(),
+        _ => {}
+    }
+}
+
+// handle keys when the projects changed on disk while we had unsaved changes
+fn handle_confirming_reload_mode(app: &mut App, key: KeyCode) {
+    match key {
+        KeyCode::Char('r') | KeyCode::Char('R') => app.reload_from_disk(),
+        KeyCode::Char('k') | KeyCode::Char('K') => app.keep_local_changes(),
+        KeyCode::Esc => app.dismiss_reload(),
+        _ => {}
+    }
+}
+
 // handle keys when selecting theme
 fn handle_theme_selector_mode(app: &mut App, key: KeyCode) {
     match key {
@@ -0,0 +1,349 @@
+//! Markdown boards in the layout of the Obsidian Kanban plugin: a `##` heading
+//! per column and a list item per task, with `#tags`, `@{due date}` and
+//! indented description and checklist lines.
+//!
+//! ```text
+//! ## In Progress
+//!
+//! - [ ] Fix login #bug !high @{2026-10-20}
+//!     Only on mobile.
+//!     - [x] reproduce
+//! ```
+//!
+//! Priorities are written as `!low` to `!urgent`, which Obsidian shows as
+//! text. Ids, timestamps and dependencies are not kept. Title words that would
+//! be read back as one of these, and description lines that would be read as
+//! checklist items, are escaped with a backslash, which Obsidian hides.
+
+use crate::board::{Board, ChecklistItem, Priority, Project, Task};
+use chrono::NaiveDate;
+
+const FRONTMATTER: &str = "---\n\nkanban-plugin: basic\n\n---\n";
+const SETTINGS: &str = "%% kanban:settings\n```\n{\"kanban-plugin\":\"basic\"}\n```\n%%\n";
+// Obsidian marks the column whose cards count as complete with this line
+const COMPLETE: &str = "**Complete**";
+const INDENT: &str = "    ";
+
+/// the board as a Markdown document
+pub fn to_markdown(project: &Project) -> String {
+    let board = &project.board;
+    let mut out = format!("{}\n", FRONTMATTER);
+    for (col_idx, column) in board.columns.iter().enumerate() {
+        out.push_str(&format!("## {}\n\n", column.name));
+        let done = board.is_done_column(col_idx);
+        if done {
+            out.push_str(COMPLETE);
+            out.push('\n');
+        }
+        for task in &column.tasks {
+            out.push_str(&task_item(task, done));
+        }
+        out.push_str("\n\n");
+    }
+    out.push('\n');
+    out.push_str(SETTINGS);
+    out
+}
+
+// the list item for a task, with its details indented below
+fn task_item(task: &Task, done: bool) -> String {
+    let title: Vec<String> = task.title.split(' ').map(escape_word).collect();
+    let mut line = format!("- [{}] {}", if done { "x" } else { " " }, title.join(" "));
+    for tag in &task.tags {
+        // a tag ends at whitespace
+        let tag: Vec<&str> = tag.split_whitespace().collect();
+        line.push_str(&format!(" #{}", tag.join("-")));
+    }
+    if task.priority != Priority::None {
+        line.push_str(&format!(" !{}", task.priority.label()));
+    }
+    if let Some(due) = task.due {
+        line.push_str(&format!(" @{{{}}}", due));
+    }
+    line.push('\n');
+
+    for text in task.description.lines() {
+        if text.trim().is_empty() {
+            line.push('\n');
+        } else if text.starts_with('\\')
+            || list_item(text.trim_start()).and_then(checkbox).is_some()
+        {
+            line.push_str(&format!("{}\\{}\n", INDENT, text));
+        } else {
+            line.push_str(&format!("{}{}\n", INDENT, text));
+        }
+    }
+    for item in &task.checklist {
+        let mark = if item.done { "x" } else { " " };
+        line.push_str(&format!("{}- [{}] {}\n", INDENT, mark, item.text));
+    }
+    line
+}
+
+// the text of a `- ` or `* ` list item
+fn list_item(line: &str) -> Option<&str> {
+    line.strip_prefix("- ").or_else(|| line.strip_prefix("* "))
+}
+
+// `[ ] text` or `[x] text`, giving (checked, text)
+fn checkbox(item: &str) -> Option<(bool, &str)> {
+    if let Some(text) = item.strip_prefix("[ ] ") {
+        Some((false, text))
+    } else {
+        item.strip_prefix("[x] ")
+            .or_else(|| item.strip_prefix("[X] "))
+            .map(|text| (true, text))
+    }
+}
+
+// `@{date}` or `@[[date]]`, giving the date as written
+fn due_word(word: &str) -> Option<&str> {
+    word.strip_prefix("@{")
+        .and_then(|date| date.strip_suffix('}'))
+        .or_else(|| {
+            word.strip_prefix("@[[")
+                .and_then(|date| date.strip_suffix("]]"))
+        })
+}
+
+fn priority_word(word: &str) -> Option<Priority> {
+    word.strip_prefix('!').and_then(Priority::from_label)
+}
+
+fn tag_word(word: &str) -> Option<&str> {
+    word.strip_prefix('#')
+        // like Obsidian, #123 is an issue number rather than a tag
+        .filter(|tag| tag.chars().any(|c| !c.is_ascii_digit() && c != '#'))
+}
+
+// a title word that would be read back as a date, priority or tag, or that
+// starts with the escaping backslash itself, is escaped
+fn escape_word(word: &str) -> String {
+    let token =
+        due_word(word).is_some() || priority_word(word).is_some() || tag_word(word).is_some();
+    if token || word.starts_with('\\') {
+        format!("\\{}", word)
+    } else {
+        word.to_string()
+    }
+}
+
+// split the first line of a card into title, tags, priority and due date
+fn parse_card(text: &str) -> Result<Task, String> {
+    let mut task = Task::new(String::new());
+    let mut title = Vec::new();
+    // split at single spaces, so runs of them stay in the title
+    for word in text.split(' ') {
+        if let Some(word) = word.strip_prefix('\\') {
+            title.push(word);
+        } else if let Some(due) = due_word(word) {
+            let date = NaiveDate::parse_from_str(due, "%Y-%m-%d")
+                .map_err(|_| format!("'{}' is not a YYYY-MM-DD date", due))?;
+            task.due = Some(date);
+        } else if let Some(priority) = priority_word(word) {
+            task.priority = priority;
+        } else if let Some(tag) = tag_word(word) {
+            task.add_tag(tag.to_string());
+        } else {
+            title.push(word);
+        }
+    }
+    task.title = title.join(" ").trim().to_string();
+    Ok(task)
+}
+
+/// read a Markdown board; `name` names the project
+pub fn parse(content: &str, name: String) -> Result<Project, String> {
+    let mut project = Project::new(name);
+    project.board = Board {
+        columns: Vec::new(),
+        blocked_gate: None,
+    };
+    // archived cards are finished: they end up in the last column
+    let mut archived = Vec::new();
+    let mut in_archive = false;
+    // description lines of the current card, blank ones kept until more follow
+    let mut blank_lines = 0;
+    let mut current: Option<Task> = None;
+
+    let board = &mut project.board;
+    // put the card read so far in the column above it
+    let finish =
+        |task: Option<Task>, board: &mut Board, archived: &mut Vec<Task>, in_archive| match (
+            task,
+            in_archive,
+            board.columns.last_mut(),
+        ) {
+            (Some(task), false, Some(column)) => column.tasks.push(task),
+            (Some(task), _, _) => archived.push(task),
+            (None, _, _) => {}
+        };
+
+    let mut lines = content.lines().enumerate();
+    // frontmatter
+    if content.starts_with("---") {
+        lines.next();
+        for (_, line) in lines.by_ref() {
+            if line.trim() == "---" {
+                break;
+            }
+        }
+    }
+    for (number, line) in lines {
+        let at = |e: String| format!("line {}: {}", number + 1, e);
+        let indented = line.starts_with(' ') || line.starts_with('\t');
+
+        if let Some(task) = current
+            .as_mut()
+            .filter(|_| indented && !line.trim().is_empty())
+        {
+            let text = line.trim_start();
+            match list_item(text).and_then(checkbox) {
+                Some((done, item)) => task.checklist.push(ChecklistItem {
+                    text: item.to_string(),
+                    done,
+                }),
+                None => {
+                    // deeper indentation is part of the text
+                    let text = line.strip_prefix(INDENT).unwrap_or(text);
+                    let text = text.strip_prefix('\\').unwrap_or(text);
+                    let description = &mut task.description;
+                    if !description.is_empty() {
+                        description.push_str(&"\n".repeat(blank_lines + 1));
+                    }
+                    description.push_str(text);
+                }
+            }
+            blank_lines = 0;
+            continue;
+        }
+        if line.trim().is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+        blank_lines = 0;
+        finish(current.take(), board, &mut archived, in_archive);
+
+        let line = line.trim();
+        if line.starts_with("%%") {
+            // the plugin's settings block comes last
+            break;
+        } else if line == "***" {
+            in_archive = true;
+        } else if let Some(name) = line.strip_prefix("## ") {
+            if !in_archive {
+                board.add_column(name.trim().to_string());
+            }
+        } else if let Some(item) = list_item(line) {
+            if board.columns.is_empty() && !in_archive {
+                return Err(at("a task before the first ## column heading".to_string()));
+            }
+            let (_, text) = checkbox(item).unwrap_or((false, item));
+            current = Some(parse_card(text).map_err(at)?);
+        }
+        // anything else, such as **Complete** or a # title, is left out
+    }
+    finish(current.take(), board, &mut archived, in_archive);
+
+    let Some(last) = board.columns.len().checked_sub(1) else {
+        return Err("no ## column headings".to_string());
+    };
+    board.columns[last].tasks.extend(archived);
+    for (col_idx, column) in board.columns.iter_mut().enumerate() {
+        for task in &mut column.tasks {
+            task.enter_column(&column.id, col_idx == last);
+        }
+    }
+    Ok(project)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut project = Project::new("Notes".to_string());
+        let mut task = Task::new("Fix login".to_string());
+        task.add_tag("bug".to_string());
+        task.add_tag("needs review".to_string());
+        task.priority = Priority::High;
+        task.due = NaiveDate::from_ymd_opt(2026, 10, 20);
+        task.description = "Only on mobile.\n\nSee the logs.".to_string();
+        task.checklist.push(ChecklistItem {
+            text: "reproduce".to_string(),
+            done: true,
+        });
+        project.board.columns[1].tasks.push(task);
+        project.board.columns[3]
+            .tasks
+            .push(Task::new("Ship #42".to_string()));
+
+        let markdown = to_markdown(&project);
+        assert!(
+            markdown.contains("- [ ] Fix login #bug #needs-review !high @{2026-10-20}\n"),
+            "{}",
+            markdown
+        );
+        assert!(markdown.contains("## Done\n\n**Complete**\n- [x] Ship #42\n"));
+
+        let parsed = parse(&markdown, "Notes".to_string()).unwrap();
+        let names: Vec<&str> = parsed
+            .board
+            .columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["To Do", "In Progress", "Testing", "Done"]);
+        assert_eq!(parsed.board.columns[1].id, "in_progress");
+        let task = &parsed.board.columns[1].tasks[0];
+        assert_eq!(task.title, "Fix login");
+        assert_eq!(task.tags, vec!["bug", "needs-review"]);
+        assert_eq!(task.priority, Priority::High);
+        assert_eq!(task.due, NaiveDate::from_ymd_opt(2026, 10, 20));
+        assert_eq!(task.description, "Only on mobile.\n\nSee the logs.");
+        assert_eq!(task.checklist.len(), 1);
+        assert!(task.checklist[0].done);
+        let shipped = &parsed.board.columns[3].tasks[0];
+        assert_eq!(shipped.title, "Ship #42");
+        assert!(shipped.completed_at.is_some());
+    }
+
+    #[test]
+    fn test_round_trip_escapes_markup() {
+        let mut project = Project::new("Notes".to_string());
+        let mut task = Task::new("Fix #42 !high  at @{2026-10-20} \\o/ #ui".to_string());
+        task.add_tag("bug".to_string());
+        task.description = "- [ ] not a checklist item\n\\ kept\nplain".to_string();
+        project.board.columns[0].tasks.push(task.clone());
+
+        let markdown = to_markdown(&project);
+        let parsed = parse(&markdown, "Notes".to_string()).unwrap();
+        let parsed_task = &parsed.board.columns[0].tasks[0];
+        assert_eq!(parsed_task.title, task.title, "{}", markdown);
+        assert_eq!(parsed_task.tags, vec!["bug"]);
+        assert_eq!(parsed_task.priority, Priority::None);
+        assert_eq!(parsed_task.due, None);
+        assert_eq!(parsed_task.description, task.description);
+        assert!(parsed_task.checklist.is_empty());
+    }
+
+    #[test]
+    fn test_parse_obsidian_board() {
+        let content = "---\n\nkanban-plugin: basic\n\n---\n\n## Ideas\n\n- [ ] Write post @[[2026-11-01]]\n\t\tdraft in notes\n\n## Doing\n\n- [ ] Talk\n\n\n## Done\n\n**Complete**\n- [x] Old\n\n***\n\n## Archive\n\n- [x] Older\n\n%% kanban:settings\n```\n{\"kanban-plugin\":\"basic\"}\n```\n%%\n";
+        let project = parse(content, "Blog".to_string()).unwrap();
+        assert_eq!(project.board.columns.len(), 3);
+        let post = &project.board.columns[0].tasks[0];
+        assert_eq!(post.due, NaiveDate::from_ymd_opt(2026, 11, 1));
+        assert_eq!(post.description, "draft in notes");
+        let done: Vec<&str> = project.board.columns[2]
+            .tasks
+            .iter()
+            .map(|t| t.title.as_str())
+            .collect();
+        assert_eq!(done, vec!["Old", "Older"]);
+
+        assert!(parse("- [ ] orphan\n", "x".to_string()).is_err());
+        assert!(parse("## Col\n- [ ] bad @{tomorrow}\n", "x".to_string()).is_err());
+    }
+}
@@ -0,0 +1,494 @@
+//! Three-way merge of a project, card by card, for `tui-kanban merge`.
+//!
+//! Git merges text line by line, which is the wrong unit for a board: two
+//! people adding cards to the bottom of the same column touch the same lines,
+//! which git reports as a conflict although nothing conflicts. Here every
+//! task, column and project setting is merged on its own. A side that didn't
+//! change something takes the other side's version; where both changed the
+//! same thing differently, ours is kept and the conflict is reported.
+
+use crate::board::{Board, BoardColumn, Project, Task};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+
+pub struct MergeResult {
+    pub project: Project,
+    // what both sides changed differently; ours was kept for each
+    pub conflicts: Vec<String>,
+}
+
+// which side a merged value came from
+#[derive(Clone, Copy, PartialEq)]
+enum Side {
+    Ours,
+    Theirs,
+}
+
+// where a task sits: its column and the task right above it
+type Placement = (String, Option<String>);
+
+// a task with where it sits on one side
+struct Located<'a> {
+    task: &'a Task,
+    placement: Placement,
+}
+
+fn locate(project: &Project) -> HashMap<&str, Located<'_>> {
+    let mut tasks = HashMap::new();
+    for column in &project.board.columns {
+        let mut above = None;
+        for task in &column.tasks {
+            tasks.insert(
+                task.id.as_str(),
+                Located {
+                    task,
+                    placement: (column.id.clone(), above.clone()),
+                },
+            );
+            above = Some(task.id.clone());
+        }
+    }
+    tasks
+}
+
+// three-way pick of a single value
+fn pick<T: PartialEq>(base: &T, ours: &T, theirs: &T) -> (Side, bool) {
+    if ours == theirs || theirs == base {
+        (Side::Ours, false)
+    } else if ours == base {
+        (Side::Theirs, false)
+    } else {
+        (Side::Ours, true)
+    }
+}
+
+// merge two versions of a serialized struct field by field
+fn merge_fields(
+    base: &Value,
+    ours: &Value,
+    theirs: &Value,
+    conflicts: &mut Vec<String>,
+    what: &str,
+) -> Value {
+    let field_map = |value: &Value| value.as_object().cloned().unwrap_or_default();
+    let (base, ours, theirs) = (field_map(base), field_map(ours), field_map(theirs));
+    let mut merged = Map::new();
+    for (key, ours_value) in &ours {
+        let base_value = base.get(key).unwrap_or(&Value::Null);
+        let theirs_value = theirs.get(key).unwrap_or(&Value::Null);
+        let value = match pick(base_value, ours_value, theirs_value) {
+            // both sides touched the card, it was last updated at the later time
+            (_, true) if key == "updated_at" => {
+                if theirs_value.as_str() > ours_value.as_str() {
+                    theirs_value
+                } else {
+                    ours_value
+                }
+            }
+            (_, true) => {
+                conflicts.push(format!("{}: both sides changed {}, kept ours", what, key));
+                ours_value
+            }
+            (Side::Ours, false) => ours_value,
+            (Side::Theirs, false) => theirs_value,
+        };
+        merged.insert(key.clone(), value.clone());
+    }
+    Value::Object(merged)
+}
+
+// a column's settings without its tasks
+fn column_settings(column: &BoardColumn) -> Value {
+    let mut value = serde_json::to_value(column).unwrap_or_default();
+    if let Some(map) = value.as_object_mut() {
+        map.remove("tasks");
+    }
+    value
+}
+
+pub fn merge_projects(base: &Project, ours: &Project, theirs: &Project) -> MergeResult {
+    let mut conflicts = Vec::new();
+
+    // project settings
+    let name = match pick(&base.name, &ours.name, &theirs.name) {
+        (Side::Theirs, _) => theirs.name.clone(),
+        (Side::Ours, conflict) => {
+            if conflict {
+                conflicts.push("project: both sides renamed it, kept ours".to_string());
+            }
+            ours.name.clone()
+        }
+    };
+    let gates = (
+        &base.board.blocked_gate,
+        &ours.board.blocked_gate,
+        &theirs.board.blocked_gate,
+    );
+    let blocked_gate = match pick(gates.0, gates.1, gates.2) {
+        (Side::Theirs, _) => gates.2.clone(),
+        (Side::Ours, conflict) => {
+            if conflict {
+                conflicts
+                    .push("project: both sides changed the blocked gate, kept ours".to_string());
+            }
+            gates.1.clone()
+        }
+    };
+
+    let columns = merge_columns(base, ours, theirs, &mut conflicts);
+    let columns = merge_tasks(base, ours, theirs, columns, &mut conflicts);
+
+    MergeResult {
+        project: Project {
+            id: ours.id.clone(),
+            name,
+            board: Board {
+                columns,
+                blocked_gate,
+            },
+        },
+        conflicts,
+    }
+}
+
+// the merged columns, still without tasks
+fn merge_columns(
+    base: &Project,
+    ours: &Project,
+    theirs: &Project,
+    conflicts: &mut Vec<String>,
+) -> Vec<BoardColumn> {
+    let find = |project: &Project, id: &str| -> Option<BoardColumn> {
+        project.board.columns.iter().find(|c| c.id == id).cloned()
+    };
+    let empty = |column: &BoardColumn| BoardColumn {
+        tasks: Vec::new(),
+        ..column.clone()
+    };
+
+    let mut columns: Vec<BoardColumn> = Vec::new();
+    for column in &ours.board.columns {
+        let what = format!("column '{}'", column.name);
+        match (find(base, &column.id), find(theirs, &column.id)) {
+            (Some(base_column), Some(theirs_column)) => {
+                let merged = merge_fields(
+                    &column_settings(&base_column),
+                    &column_settings(column),
+                    &column_settings(&theirs_column),
+                    conflicts,
+                    &what,
+                );
+                match serde_json::from_value::<BoardColumn>(with_tasks(merged)) {
+                    Ok(merged) => columns.push(merged),
+                    Err(_) => columns.push(empty(column)),
+                }
+            }
+            // removed by them: gone, unless we changed it since
+            (Some(base_column), None) => {
+                if column_settings(&base_column) != column_settings(column) {
+                    conflicts.push(format!(
+                        "{}: removed by theirs but changed by ours, kept it",
+                        what
+                    ));
+                    columns.push(empty(column));
+                }
+            }
+            // added by us
+            (None, _) => columns.push(empty(column)),
+        }
+    }
+
+    // columns they added go after the column they follow on their side
+    for (i, column) in theirs.board.columns.iter().enumerate() {
+        match (find(base, &column.id), find(ours, &column.id)) {
+            // merged above
+            (_, Some(_)) => continue,
+            // removed by us
+            (Some(base_column), None) => {
+                if column_settings(&base_column) != column_settings(column) {
+                    conflicts.push(format!(
+                        "column '{}': removed by ours but changed by theirs, left out",
+                        column.name
+                    ));
+                }
+                continue;
+            }
+            (None, None) => {}
+        }
+        let at = theirs.board.columns[..i]
+            .iter()
+            .rev()
+            .find_map(|before| columns.iter().position(|c| c.id == before.id))
+            .map_or(0, |index| index + 1);
+        columns.insert(at, empty(column));
+    }
+    columns
+}
+
+fn with_tasks(mut column: Value) -> Value {
+    if let Some(map) = column.as_object_mut() {
+        map.insert("tasks".to_string(), Value::Array(Vec::new()));
+    }
+    column
+}
+
+// fill the merged columns with the merged tasks
+fn merge_tasks(
+    base: &Project,
+    ours: &Project,
+    theirs: &Project,
+    mut columns: Vec<BoardColumn>,
+    conflicts: &mut Vec<String>,
+) -> Vec<BoardColumn> {
+    let base_tasks = locate(base);
+    let ours_tasks = locate(ours);
+    let theirs_tasks = locate(theirs);
+
+    // merged task and which side decides where it goes
+    let mut merged: HashMap<String, (Task, Side)> = HashMap::new();
+    let ids: HashSet<&str> = ours_tasks
+        .keys()
+        .chain(theirs_tasks.keys())
+        .copied()
+        .collect();
+    for id in ids {
+        let located = (base_tasks.get(id), ours_tasks.get(id), theirs_tasks.get(id));
+        let entry = match located {
+            (Some(b), Some(o), Some(t)) => {
+                let what = format!("task '{}'", o.task.title);
+                let side = match pick(&b.placement, &o.placement, &t.placement) {
+                    (side, false) => side,
+                    (_, true) if o.placement.0 == t.placement.0 => Side::Ours,
+                    (_, true) => {
+                        conflicts.push(format!("{}: moved to different columns, kept ours", what));
+                        Side::Ours
+                    }
+                };
+                // The column history and completion time go with where the
+                // card ends up; in the same column, with whoever moved it
+                let history_side = if o.placement.0 == t.placement.0 {
+                    let history = |side: &Located| side.task.column_history.clone();
+                    pick(&history(b), &history(o), &history(t)).0
+                } else {
+                    side
+                };
+                let winner = if history_side == Side::Ours { o } else { t };
+                let follow = |task: &Task| Task {
+                    column_history: winner.task.column_history.clone(),
+                    completed_at: winner.task.completed_at,
+                    ..task.clone()
+                };
+                let task = merge_fields(
+                    &to_value(b.task),
+                    &to_value(&follow(o.task)),
+                    &to_value(&follow(t.task)),
+                    conflicts,
+                    &what,
+                );
+                let task = serde_json::from_value(task).unwrap_or_else(|_| o.task.clone());
+                Some((task, side))
+            }
+            // deleted by them: gone, unless we changed it since
+            (Some(b), Some(o), None) => {
+                if to_value(b.task) != to_value(o.task) {
+                    conflicts.push(format!(
+                        "task '{}': deleted by theirs but changed by ours, kept it",
+                        o.task.title
+                    ));
+                    Some((o.task.clone(), Side::Ours))
+                } else {
+                    None
+                }
+            }
+            (Some(b), None, Some(t)) => {
+                if to_value(b.task) != to_value(t.task) {
+                    conflicts.push(format!(
+                        "task '{}': deleted by ours but changed by theirs, kept it",
+                        t.task.title
+                    ));
+                    Some((t.task.clone(), Side::Theirs))
+                } else {
+                    None
+                }
+            }
+            // added on one side (or, with the same id, on both)
+            (None, Some(o), _) => Some((o.task.clone(), Side::Ours)),
+            (None, None, Some(t)) => Some((t.task.clone(), Side::Theirs)),
+            (_, None, None) => None,
+        };
+        if let Some(entry) = entry {
+            merged.insert(id.to_string(), entry);
+        }
+    }
+
+    // our order first, for the tasks where we decide
+    let mut homeless = Vec::new();
+    for column in &ours.board.columns {
+        for task in &column.tasks {
+            if !matches!(merged.get(&task.id), Some((_, Side::Ours))) {
+                continue;
+            }
+            let Some((task, _)) = merged.remove(&task.id) else {
+                continue;
+            };
+            match columns.iter_mut().find(|c| c.id == column.id) {
+                Some(target) => target.tasks.push(task),
+                None => homeless.push(task),
+            }
+        }
+    }
+
+    // then theirs, each after the task it follows on their side
+    for column in &theirs.board.columns {
+        for (i, task) in column.tasks.iter().enumerate() {
+            let Some((task, Side::Theirs)) = merged.remove(&task.id) else {
+                continue;
+            };
+            let Some(target) = columns.iter_mut().find(|c| c.id == column.id) else {
+                homeless.push(task);
+                continue;
+            };
+            let at = column.tasks[..i]
+                .iter()
+                .rev()
+                .find_map(|above| target.tasks.iter().position(|t| t.id == above.id))
+                .map_or(0, |index| index + 1);
+            target.tasks.insert(at, task);
+        }
+    }
+
+    // tasks whose column was removed on the other side
+    if !homeless.is_empty() {
+        for task in &homeless {
+            conflicts.push(format!(
+                "task '{}': its column was removed, moved to the first column",
+                task.title
+            ));
+        }
+        if let Some(first) = columns.first_mut() {
+            first.tasks.extend(homeless);
+        }
+    }
+    columns
+}
+
+fn to_value(task: &Task) -> Value {
+    serde_json::to_value(task).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base_project() -> Project {
+        let mut project = Project::new("Website".to_string());
+        for title in ["One", "Two", "Three"] {
+            project.board.columns[0]
+                .tasks
+                .push(Task::new(title.to_string()));
+        }
+        project
+    }
+
+    fn titles(project: &Project, column: usize) -> Vec<&str> {
+        project.board.columns[column]
+            .tasks
+            .iter()
+            .map(|task| task.title.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_different_cards_merge_cleanly() {
+        let base = base_project();
+
+        // we move One to the second column and add a card at the bottom
+        let mut ours = base.clone();
+        let one = ours.board.columns[0].tasks.remove(0);
+        ours.board.columns[1].tasks.push(one);
+        ours.board.columns[0]
+            .tasks
+            .push(Task::new("Ours".to_string()));
+
+        // they retitle Three, add a card at the bottom and a column
+        let mut theirs = base.clone();
+        theirs.board.columns[0].tasks[2].title = "Three (renamed)".to_string();
+        theirs.board.columns[0]
+            .tasks
+            .push(Task::new("Theirs".to_string()));
+        theirs.board.columns.insert(
+            1,
+            BoardColumn::new("review".to_string(), "Review".to_string()),
+        );
+
+        let result = merge_projects(&base, &ours, &theirs);
+        assert!(result.conflicts.is_empty(), "{:?}", result.conflicts);
+        let merged = result.project;
+        assert_eq!(merged.board.columns[1].id, "review");
+        assert_eq!(
+            titles(&merged, 0),
+            vec!["Two", "Three (renamed)", "Theirs", "Ours"]
+        );
+        assert_eq!(titles(&merged, 2), vec!["One"]);
+    }
+
+    #[test]
+    fn test_same_field_conflict_keeps_ours() {
+        let base = base_project();
+        let mut ours = base.clone();
+        ours.board.columns[0].tasks[0].title = "Ours".to_string();
+        ours.board.columns[0].tasks[0].touch();
+        let mut theirs = base.clone();
+        theirs.board.columns[0].tasks[0].title = "Theirs".to_string();
+        theirs.board.columns[0].tasks[0]
+            .tags
+            .push("docs".to_string());
+        theirs.board.columns[0].tasks[0].touch();
+
+        let result = merge_projects(&base, &ours, &theirs);
+        assert_eq!(result.conflicts.len(), 1, "{:?}", result.conflicts);
+        assert!(result.conflicts[0].contains("title"));
+        let task = &result.project.board.columns[0].tasks[0];
+        assert_eq!(task.title, "Ours");
+        assert_eq!(task.tags, vec!["docs".to_string()]);
+    }
+
+    #[test]
+    fn test_same_move_on_both_sides() {
+        let base = base_project();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        for (side, minutes) in [(&mut ours, 1), (&mut theirs, 2)] {
+            let mut task = side.board.columns[0].tasks.remove(0);
+            task.enter_column("done", true);
+            let at = task.completed_at.unwrap() + chrono::Duration::minutes(minutes);
+            task.column_history.last_mut().unwrap().entered_at = at;
+            task.completed_at = Some(at);
+            side.board.columns[3].tasks.push(task);
+        }
+
+        let result = merge_projects(&base, &ours, &theirs);
+        assert!(result.conflicts.is_empty(), "{:?}", result.conflicts);
+        let task = &result.project.board.columns[3].tasks[0];
+        let ours_task = &ours.board.columns[3].tasks[0];
+        assert_eq!(task.column_history, ours_task.column_history);
+        assert_eq!(task.completed_at, ours_task.completed_at);
+        assert_eq!(titles(&result.project, 0), vec!["Two", "Three"]);
+    }
+
+    #[test]
+    fn test_deletions() {
+        let base = base_project();
+        let mut ours = base.clone();
+        ours.board.columns[0].tasks.remove(0);
+        let mut theirs = base.clone();
+        theirs.board.columns[0].tasks.remove(1);
+        theirs.board.columns[0].tasks[1].title = "Changed".to_string();
+        ours.board.columns[0].tasks.remove(1);
+
+        let result = merge_projects(&base, &ours, &theirs);
+        // One and Two are gone, Three was changed by them so it stays
+        assert_eq!(titles(&result.project, 0), vec!["Changed"]);
+        assert_eq!(result.conflicts.len(), 1, "{:?}", result.conflicts);
+    }
+}
@@ -0,0 +1,355 @@
+use crate::board::Project;
+use crate::board_text;
+use crate::merge;
+use crate::schema::{self, ParseError};
+use crate::storage::{self, Backend, FileStamp, LoadError};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// folder holding the board of a repository, committed alongside its code
+pub const BOARD_DIR: &str = ".tui-kanban";
+
+// the text format merges well, JSON is read too
+const BOARD_FILES: [&str; 2] = ["board.kanban", "board.json"];
+
+// routes merges of the board through `tui-kanban merge`
+const GITATTRIBUTES: &str = "board.kanban merge=tui-kanban\n";
+
+/// find the repository board in `start` or the closest parent directory that has one
+pub fn find_repo_board(start: &Path) -> Option<PathBuf> {
+    start.ancestors().find_map(|dir| {
+        BOARD_FILES
+            .iter()
+            .map(|file| dir.join(BOARD_DIR).join(file))
+            .find(|path| path.is_file())
+    })
+}
+
+/// create an empty board in `dir`, named after the directory
+pub fn init_repo_board(dir: &Path) -> Result<PathBuf, String> {
+    if let Some(existing) = BOARD_FILES
+        .iter()
+        .map(|file| dir.join(BOARD_DIR).join(file))
+        .find(|path| path.exists())
+    {
+        return Err(format!("{} already exists", existing.display()));
+    }
+    let path = dir.join(BOARD_DIR).join(BOARD_FILES[0]);
+    let name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "Board".to_string());
+    let mut project = Project::new(name);
+    project.ensure_ids();
+
+    let content = board_to_string(&project, &path).map_err(|e| e.to_string())?;
+    let attributes = dir.join(BOARD_DIR).join(".gitattributes");
+    fs::create_dir_all(dir.join(BOARD_DIR))
+        .and_then(|_| storage::write_atomic(&path, content.as_bytes()))
+        .and_then(|_| match attributes.exists() {
+            true => Ok(()),
+            false => fs::write(&attributes, GITATTRIBUTES),
+        })
+        .map_err(|e| format!("Could not create {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+/// the configured backend with its projects loaded
+pub struct Opened {
+    pub backend: Box<dyn Backend>,
+    pub projects: Result<Vec<Project>, LoadError>,
+    // id of the repository board, when run inside a repository that has one
+    pub repo_project: Option<String>,
+}
+
+/// open the backend chosen in config.json and load it, adding the board of the
+/// repository we're in, if any; see `Backend::load` for `read_only`
+pub fn open_projects(config: &storage::Config, read_only: bool) -> Opened {
+    let repo_board = std::env::current_dir()
+        .ok()
+        .and_then(|dir| find_repo_board(&dir));
+    match (storage::open_backend(config), repo_board) {
+        (Ok(backend), Some(path)) => {
+            let mut backend = RepoBoardBackend::new(backend, path);
+            let projects = backend.load(read_only);
+            let repo_project = backend.project_id().map(String::from);
+            Opened {
+                backend: Box::new(backend),
+                projects,
+                repo_project,
+            }
+        }
+        (Ok(mut backend), None) => {
+            let projects = backend.load(read_only);
+            Opened {
+                backend,
+                projects,
+                repo_project: None,
+            }
+        }
+        (Err(e), _) => Opened {
+            backend: storage::default_backend(config),
+            projects: Err(LoadError::Failed(e)),
+            repo_project: None,
+        },
+    }
+}
+
+/// parse a board in either the text or the JSON format
+pub fn parse_board(content: &str) -> Result<Project, ParseError> {
+    if board_text::is_text(content) {
+        Ok(board_text::parse(content)?)
+    } else {
+        schema::parse_project(content)
+    }
+}
+
+// serialize in the format the file name asks for
+fn board_to_string(project: &Project, path: &Path) -> serde_json::Result<String> {
+    if path.extension().is_some_and(|ext| ext == "kanban") {
+        Ok(board_text::to_text(project))
+    } else {
+        schema::to_project_json(project)
+    }
+}
+
+/// three-way merge of board files as a git merge driver: the result replaces
+/// `ours`, in the format `ours` was in; returns the conflicts, where ours was kept
+pub fn merge_files(base: &Path, ours: &Path, theirs: &Path) -> Result<Vec<String>, String> {
+    let read = |path: &Path| {
+        fs::read_to_string(path)
+            .map_err(|e| format!("Could not read {}: {}", path.display(), e))
+            .and_then(|content| {
+                let project = parse_board(&content)
+                    .map_err(|e| format!("{} is not a board: {}", path.display(), e))?;
+                Ok((project, content))
+            })
+    };
+    let (ours_project, ours_content) = read(ours)?;
+    let (theirs_project, _) = read(theirs)?;
+    // Added on both sides: there is no common version, so nothing was removed
+    let base_project = match read(base) {
+        Ok((project, _)) => project,
+        Err(_) if fs::metadata(base).is_ok_and(|meta| meta.len() == 0) => Project {
+            board: crate::board::Board {
+                columns: Vec::new(),
+                blocked_gate: None,
+            },
+            ..ours_project.clone()
+        },
+        Err(e) => return Err(e),
+    };
+
+    let result = merge::merge_projects(&base_project, &ours_project, &theirs_project);
+    let content = if board_text::is_text(&ours_content) {
+        board_text::to_text(&result.project)
+    } else {
+        schema::to_project_json(&result.project).map_err(|e| e.to_string())?
+    };
+    storage::write_atomic(ours, content.as_bytes()).map_err(|e| e.to_string())?;
+    Ok(result.conflicts)
+}
+
+/// the projects of another backend plus the repository board, which is listed
+/// first and saved to its own file instead of the global data
+pub struct RepoBoardBackend {
+    inner: Box<dyn Backend>,
+    path: PathBuf,
+    project_id: Option<String>,
+    // file contents as we last read or wrote them, so saving other projects
+    // doesn't touch the file (and the working tree) at all
+    written: Option<String>,
+}
+
+impl RepoBoardBackend {
+    pub fn new(inner: Box<dyn Backend>, path: PathBuf) -> Self {
+        Self {
+            inner,
+            path,
+            project_id: None,
+            written: None,
+        }
+    }
+
+    /// id of the repository board once loaded
+    pub fn project_id(&self) -> Option<&str> {
+        self.project_id.as_deref()
+    }
+
+    // put the repository board in front of the other projects; with
+    // `write_ids` set, ids filled in for a board that lacked them are saved
+    fn with_repo_project(
+        &mut self,
+        projects: Vec<Project>,
+        write_ids: bool,
+    ) -> Result<Vec<Project>, LoadError> {
+        let content = fs::read_to_string(&self.path)
+            .map_err(|e| format!("Could not read {}: {}", self.path.display(), e))?;
+        // Not moved aside when corrupt: it's tracked by git, which can restore it
+        let mut project = parse_board(&content).map_err(|e| match e {
+            ParseError::Newer(_) => LoadError::Newer(format!(
+                "{} can't be opened: {}. Update tui-kanban to use it.",
+                self.path.display(),
+                e
+            )),
+            ParseError::Invalid(_) => {
+                LoadError::Failed(format!("{} is corrupt: {}.", self.path.display(), e))
+            }
+        })?;
+        self.written = Some(content);
+        // Otherwise every start would mint new ids for a hand-written board
+        if project.ensure_ids() && write_ids {
+            if let Ok(content) = board_to_string(&project, &self.path) {
+                if storage::write_atomic(&self.path, content.as_bytes()).is_ok() {
+                    self.written = Some(content);
+                }
+            }
+        }
+
+        self.project_id = Some(project.id.clone());
+        let mut all = vec![project];
+        all.extend(
+            projects
+                .into_iter()
+                .filter(|p| Some(&p.id) != self.project_id.as_ref()),
+        );
+        Ok(all)
+    }
+}
+
+impl Backend for RepoBoardBackend {
+    fn load(&mut self, read_only: bool) -> Result<Vec<Project>, LoadError> {
+        let projects = self.inner.load(read_only)?;
+        self.with_repo_project(projects, !read_only)
+    }
+
+    fn reload(&mut self) -> Result<Vec<Project>, LoadError> {
+        let projects = self.inner.reload()?;
+        self.with_repo_project(projects, false)
+    }
+
+    fn save(&mut self, projects: &[Project]) -> Result<(), Box<dyn std::error::Error>> {
+        let (repo, others): (Vec<Project>, Vec<Project>) = projects
+            .iter()
+            .cloned()
+            .partition(|p| Some(&p.id) == self.project_id.as_ref());
+
+        // A removed repository board stays in the repository
+        if let Some(project) = repo.first() {
+            let content = board_to_string(project, &self.path)?;
+            if self.written.as_ref() != Some(&content) {
+                storage::write_atomic(&self.path, content.as_bytes())?;
+                self.written = Some(content);
+            }
+        }
+        self.inner.save(&others)
+    }
+
+    fn stamp(&self) -> Option<FileStamp> {
+        FileStamp::combine(self.inner.stamp(), storage::file_stamp(&self.path))
+    }
+
+    fn pending_corrupt(&self) -> Vec<PathBuf> {
+        self.inner.pending_corrupt()
+    }
+
+    fn resolve_corrupt(&mut self) {
+        self.inner.resolve_corrupt();
+    }
+
+    // the repository board is versioned with the repository, not backed up
+    fn backup(&mut self, keep: usize) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+        self.inner.backup(keep)
+    }
+
+    fn restore(
+        &mut self,
+        projects: &[Project],
+        keep: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.inner.restore(projects, keep)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::JsonBackend;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_find_repo_board_in_parent() {
+        let dir = tempdir().unwrap();
+        let nested = dir.path().join("src/deep");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(find_repo_board(&nested), None);
+
+        let path = init_repo_board(dir.path()).unwrap();
+        assert_eq!(find_repo_board(&nested), Some(path));
+        assert!(init_repo_board(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_repo_board_is_saved_to_its_own_file() {
+        let dir = tempdir().unwrap();
+        let repo = dir.path().join("my-repo");
+        let path = init_repo_board(&repo).unwrap();
+        let inner = JsonBackend::new(dir.path().join("projects"));
+        let mut backend = RepoBoardBackend::new(Box::new(inner), path.clone());
+
+        let mut projects = backend.load(false).unwrap();
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].name, "my-repo");
+        assert_eq!(backend.project_id(), Some(projects[0].id.as_str()));
+
+        // editing a global project leaves the repository file alone
+        let repo_stamp = storage::file_stamp(&path);
+        projects[1].name = "Personal".to_string();
+        backend.save(&projects).unwrap();
+        assert_eq!(storage::file_stamp(&path), repo_stamp);
+
+        projects[0].name = "Team".to_string();
+        backend.save(&projects).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(board_text::is_text(&content));
+        assert_eq!(parse_board(&content).unwrap().name, "Team");
+
+        let global = JsonBackend::new(dir.path().join("projects"))
+            .load(false)
+            .unwrap();
+        assert_eq!(global.len(), 1);
+        assert_eq!(global[0].name, "Personal");
+    }
+
+    #[test]
+    fn test_backfilled_ids_are_saved() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(BOARD_DIR).join("board.json");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut project = Project::new("Legacy".to_string());
+        project.board.columns[0]
+            .tasks
+            .push(crate::board::Task::new("Hand-written".to_string()));
+        project.id.clear();
+        project.board.columns[0].tasks[0].id.clear();
+        fs::write(&path, schema::to_project_json(&project).unwrap()).unwrap();
+        let open = || {
+            let inner = JsonBackend::new(dir.path().join("projects"));
+            RepoBoardBackend::new(Box::new(inner), path.clone())
+        };
+
+        // a read-only load leaves the file alone
+        let before = fs::read_to_string(&path).unwrap();
+        open().load(true).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), before);
+
+        let first = open().load(false).unwrap();
+        let second = open().load(false).unwrap();
+        assert!(!first[0].id.is_empty());
+        assert_eq!(first[0].id, second[0].id);
+        assert_eq!(
+            first[0].board.columns[0].tasks[0].id,
+            second[0].board.columns[0].tasks[0].id
+        );
+    }
+}
@@ -0,0 +1,347 @@
+use crate::board::{Board, BoardColumn, ColumnSort, Project, Task};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+// Version of the projects.json layout written by this build. Bump it and add
+// a step to MIGRATIONS whenever the on-disk format changes in a way
+// `#[serde(default)]` can't absorb.
+//
+// 1 - omarchy-kanban board.json: a single board with fixed todo/in_progress/testing/done lists
+// 2 - omarchy-kanban projects.json: a list of named projects holding such boards
+// 3 - a bare list of projects with configurable columns
+// 4 - `{ "schema_version": 4, "projects": [...] }`, every project and task has an id
+pub const CURRENT_SCHEMA_VERSION: u32 = 4;
+
+/// why a projects file can't be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// not JSON, or not a tui-kanban file this build can make sense of
+    Invalid(String),
+    /// written by a newer tui-kanban; the file itself may well be fine
+    Newer(u64),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Invalid(e) => write!(f, "{}", e),
+            ParseError::Newer(version) => write!(
+                f,
+                "schema_version {} was written by a newer tui-kanban (this one reads up to {})",
+                version, CURRENT_SCHEMA_VERSION
+            ),
+        }
+    }
+}
+
+impl From<String> for ParseError {
+    fn from(e: String) -> Self {
+        ParseError::Invalid(e)
+    }
+}
+
+// upgrade steps in order; entry `i` turns version `i + 1` into version `i + 2`
+const MIGRATIONS: [fn(Value) -> Result<Value, String>; 3] = [
+    board_to_projects,
+    fixed_to_custom_columns,
+    wrap_with_version,
+];
+
+// the current file layout (schema_version is checked before deserializing)
+#[derive(Deserialize)]
+struct SaveFile {
+    projects: Vec<Project>,
+}
+
+// a single project file in the projects/ folder
+#[derive(Deserialize)]
+struct ProjectFile {
+    project: Project,
+}
+
+// This struct represents the old Board structure for migration purposes
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct LegacyBoard {
+    pub todo: Vec<Task>,
+    pub in_progress: Vec<Task>,
+    pub testing: Vec<Task>,
+    pub done: Vec<Task>,
+}
+
+// For projects.json (intermediate format)
+#[derive(Deserialize, Serialize)]
+struct LegacyProject {
+    name: String,
+    board: LegacyBoard, // Uses the old board structure
+}
+
+// Conversion logic from LegacyBoard to new Board format
+impl From<LegacyBoard> for Board {
+    fn from(legacy_board: LegacyBoard) -> Self {
+        Board {
+            columns: vec![
+                BoardColumn {
+                    id: "todo".to_string(),
+                    name: "To Do".to_string(),
+                    tasks: legacy_board.todo,
+                    sort: ColumnSort::Manual,
+                    wip_limit: None,
+                },
+                BoardColumn {
+                    id: "in_progress".to_string(),
+                    name: "In Progress".to_string(),
+                    tasks: legacy_board.in_progress,
+                    sort: ColumnSort::Manual,
+                    wip_limit: None,
+                },
+                BoardColumn {
+                    id: "testing".to_string(),
+                    name: "Testing".to_string(),
+                    tasks: legacy_board.testing,
+                    sort: ColumnSort::Manual,
+                    wip_limit: None,
+                },
+                BoardColumn {
+                    id: "done".to_string(),
+                    name: "Done".to_string(),
+                    tasks: legacy_board.done,
+                    sort: ColumnSort::Manual,
+                    wip_limit: None,
+                },
+            ],
+            blocked_gate: None,
+        }
+    }
+}
+
+// Conversion logic from LegacyProject to new Project format
+impl From<LegacyProject> for Project {
+    fn from(legacy_project: LegacyProject) -> Self {
+        Project {
+            id: String::new(), // assigned by ensure_ids
+            name: legacy_project.name,
+            board: legacy_project.board.into(), // Use the From<LegacyBoard> impl
+        }
+    }
+}
+
+/// serialize projects in the current file layout
+pub fn to_json(projects: &[Project]) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(&serde_json::json!({
+        "schema_version": CURRENT_SCHEMA_VERSION,
+        "projects": projects,
+    }))
+}
+
+/// parse a projects file of any known version, upgrading it to the current one;
+/// the flag is true if a migration ran and the file should be written back
+pub fn parse_projects(content: &str) -> Result<(Vec<Project>, bool), ParseError> {
+    let value: Value = serde_json::from_str(content).map_err(|e| describe_json_error(&e))?;
+    let version = detect_version(&value)?;
+
+    if version == CURRENT_SCHEMA_VERSION {
+        // Parse the text again so type errors point at a line and column
+        let file: SaveFile = serde_json::from_str(content).map_err(|e| describe_json_error(&e))?;
+        return Ok((file.projects, false));
+    }
+
+    let mut value = value;
+    for step in &MIGRATIONS[(version - 1) as usize..] {
+        value = step(value)?;
+    }
+    let file: SaveFile = serde_json::from_value(value)
+        .map_err(|e| format!("upgrading from version {} failed: {}", version, e))?;
+    Ok((file.projects, true))
+}
+
+/// serialize a single project for the one-file-per-project layout
+pub fn to_project_json(project: &Project) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(&serde_json::json!({
+        "schema_version": CURRENT_SCHEMA_VERSION,
+        "project": project,
+    }))
+}
+
+/// parse a file written by `to_project_json`; project files only exist since
+/// version 4, so there is nothing to migrate yet
+pub fn parse_project(content: &str) -> Result<Project, ParseError> {
+    parse_versioned::<ProjectFile>(content, "not a tui-kanban project file")
+        .map(|file| file.project)
+}
+
+/// parse a file that has carried a schema_version from the start, such as
+/// index.json, refusing versions this build doesn't know
+pub fn parse_versioned<T: DeserializeOwned>(
+    content: &str,
+    unversioned: &str,
+) -> Result<T, ParseError> {
+    let value: Value = serde_json::from_str(content).map_err(|e| describe_json_error(&e))?;
+    if value.get("schema_version").is_none() {
+        return Err(ParseError::Invalid(unversioned.to_string()));
+    }
+    detect_version(&value)?;
+    Ok(serde_json::from_str(content).map_err(|e| describe_json_error(&e))?)
+}
+
+// work out which version wrote the file; files before version 4 carry no
+// version number, so they are told apart by their shape
+fn detect_version(value: &Value) -> Result<u32, ParseError> {
+    if let Some(version) = value.get("schema_version") {
+        let version = version
+            .as_u64()
+            .filter(|version| *version >= 1)
+            .ok_or_else(|| format!("invalid schema_version {}", version))?;
+        if version > CURRENT_SCHEMA_VERSION as u64 {
+            return Err(ParseError::Newer(version));
+        }
+        return Ok(version as u32);
+    }
+
+    match value {
+        Value::Object(map) if map.contains_key("todo") => Ok(1),
+        Value::Array(projects) => {
+            let legacy = projects
+                .first()
+                .and_then(|project| project.get("board"))
+                .is_some_and(|board| board.get("todo").is_some());
+            Ok(if legacy { 2 } else { 3 })
+        }
+        _ => Err(ParseError::Invalid(
+            "not a tui-kanban projects file".to_string(),
+        )),
+    }
+}
+
+// serde_json's message with the position spelled out
+fn describe_json_error(error: &serde_json::Error) -> String {
+    let message = error.to_string();
+    let suffix = format!(" at line {} column {}", error.line(), error.column());
+    format!(
+        "{} (line {}, column {})",
+        message.strip_suffix(&suffix).unwrap_or(&message),
+        error.line(),
+        error.column()
+    )
+}
+
+// 1 -> 2: the single board becomes a project named "Default"
+fn board_to_projects(board: Value) -> Result<Value, String> {
+    Ok(serde_json::json!([{ "name": "Default", "board": board }]))
+}
+
+// 2 -> 3: fixed todo/in_progress/testing/done lists become columns
+fn fixed_to_custom_columns(projects: Value) -> Result<Value, String> {
+    let legacy: Vec<LegacyProject> = serde_json::from_value(projects)
+        .map_err(|e| format!("upgrading from version 2 failed: {}", e))?;
+    let projects: Vec<Project> = legacy.into_iter().map(Into::into).collect();
+    serde_json::to_value(projects).map_err(|e| e.to_string())
+}
+
+// 3 -> 4: wrap in a versioned object and give every project and task an id
+fn wrap_with_version(projects: Value) -> Result<Value, String> {
+    let mut projects: Vec<Project> = serde_json::from_value(projects)
+        .map_err(|e| format!("upgrading from version 3 failed: {}", e))?;
+    for project in projects.iter_mut() {
+        project.ensure_ids();
+    }
+    Ok(serde_json::json!({ "schema_version": 4, "projects": projects }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TASK: &str = r#"{"title": "Write docs", "tags": ["docs"], "description": ""}"#;
+
+    fn legacy_board() -> String {
+        format!(
+            r#"{{"todo": [{}], "in_progress": [], "testing": [], "done": []}}"#,
+            TASK
+        )
+    }
+
+    fn assert_upgraded(projects: &[Project], name: &str) {
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].name, name);
+        assert!(!projects[0].id.is_empty());
+        let columns = &projects[0].board.columns;
+        assert_eq!(columns.len(), 4);
+        assert_eq!(columns[0].id, "todo");
+        assert_eq!(columns[0].tasks[0].title, "Write docs");
+        assert!(!columns[0].tasks[0].id.is_empty());
+    }
+
+    #[test]
+    fn test_version_1_board() {
+        let (projects, migrated) = parse_projects(&legacy_board()).unwrap();
+        assert!(migrated);
+        assert_upgraded(&projects, "Default");
+    }
+
+    #[test]
+    fn test_version_2_legacy_projects() {
+        let content = format!(r#"[{{"name": "Work", "board": {}}}]"#, legacy_board());
+        let (projects, migrated) = parse_projects(&content).unwrap();
+        assert!(migrated);
+        assert_upgraded(&projects, "Work");
+    }
+
+    #[test]
+    fn test_version_3_project_list() {
+        // columns without sort/wip_limit and tasks without ids or dates
+        let content = format!(
+            r#"[{{"name": "Work", "board": {{"columns": [
+                {{"id": "todo", "name": "To Do", "tasks": [{}]}},
+                {{"id": "in_progress", "name": "In Progress", "tasks": []}},
+                {{"id": "testing", "name": "Testing", "tasks": []}},
+                {{"id": "done", "name": "Done", "tasks": []}}
+            ]}}}}]"#,
+            TASK
+        );
+        let (projects, migrated) = parse_projects(&content).unwrap();
+        assert!(migrated);
+        assert_upgraded(&projects, "Work");
+
+        // an empty list is a version 3 file too
+        let (projects, migrated) = parse_projects("[]").unwrap();
+        assert!(projects.is_empty() && migrated);
+    }
+
+    #[test]
+    fn test_current_version_round_trip() {
+        let (projects, _) = parse_projects(&legacy_board()).unwrap();
+        let json = to_json(&projects).unwrap();
+        assert!(json.contains("\"schema_version\": 4"));
+
+        let (reloaded, migrated) = parse_projects(&json).unwrap();
+        assert!(!migrated);
+        assert_eq!(reloaded[0].id, projects[0].id);
+        assert_eq!(
+            reloaded[0].board.columns[0].tasks[0].id,
+            projects[0].board.columns[0].tasks[0].id
+        );
+    }
+
+    #[test]
+    fn test_unknown_and_newer_versions() {
+        let error = parse_projects(r#"{"schema_version": 99, "projects": []}"#).unwrap_err();
+        assert_eq!(error, ParseError::Newer(99));
+        assert!(error.to_string().contains("newer"), "{}", error);
+        let error = parse_project(r#"{"schema_version": 5, "project": 1}"#).unwrap_err();
+        assert_eq!(error, ParseError::Newer(5));
+        assert!(parse_projects(r#"{"schema_version": 0, "projects": []}"#).is_err());
+        assert!(parse_projects(r#""hello""#).is_err());
+    }
+
+    #[test]
+    fn test_errors_carry_position() {
+        let error = parse_projects("{\"schema_version\": 4,\n \"projects\": 5}")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("line 2"), "{}", error);
+        let error = parse_projects("[\n  {,\n]").unwrap_err().to_string();
+        assert!(error.contains("line 2, column 4"), "{}", error);
+    }
+}
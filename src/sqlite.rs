@@ -0,0 +1,500 @@
+use crate::board::{Board, BoardColumn, ColumnSort, Project, Task};
+use crate::storage::{self, Backend, FileStamp, JsonBackend, LoadError};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+// bump together with a migration in `create_schema` when the tables change
+const DB_SCHEMA_VERSION: i64 = 1;
+
+// everything about a project except its tasks, stored as JSON in one row
+#[derive(Serialize, Deserialize)]
+struct ProjectRow {
+    name: String,
+    columns: Vec<ColumnRow>,
+    #[serde(default)]
+    blocked_gate: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ColumnRow {
+    id: String,
+    name: String,
+    #[serde(default)]
+    sort: ColumnSort,
+    #[serde(default)]
+    wip_limit: Option<usize>,
+}
+
+// a task row: where it sits, plus the task itself as JSON
+#[derive(PartialEq)]
+struct TaskRow {
+    column: usize,
+    position: usize,
+    data: String,
+}
+
+/// projects kept in SQLite; a save only writes the rows that changed since the
+/// last load or save, so one edit on a board with thousands of cards stays cheap
+pub struct SqliteBackend {
+    path: PathBuf,
+    conn: Connection,
+    // JSON projects to import when the database is still empty; its backups
+    // folder is shared, so backups restore into either backend
+    import: JsonBackend,
+    // rows as they are in the database, keyed by project id and (project id, task id)
+    projects: HashMap<String, (usize, String)>,
+    tasks: HashMap<(String, String), TaskRow>,
+    // database as we last read or wrote it; if it changed, the cache above is stale
+    stamp: Option<FileStamp>,
+}
+
+impl SqliteBackend {
+    pub fn open(path: &Path, import: JsonBackend) -> Result<Self, String> {
+        let conn = Connection::open(path)
+            .map_err(|e| format!("Could not open {}: {}", path.display(), e))?;
+        create_schema(&conn).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            conn,
+            import,
+            projects: HashMap::new(),
+            tasks: HashMap::new(),
+            stamp: None,
+        })
+    }
+
+    fn read_all(&mut self) -> rusqlite::Result<Vec<Project>> {
+        self.projects.clear();
+        self.tasks.clear();
+
+        let mut projects = Vec::new();
+        let mut statement = self
+            .conn
+            .prepare("SELECT id, position, data FROM projects ORDER BY position")?;
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?;
+        for row in rows {
+            let (id, position, data) = row?;
+            let project_row: ProjectRow = from_json(&data)?;
+            let columns = project_row
+                .columns
+                .into_iter()
+                .map(|column| BoardColumn {
+                    id: column.id,
+                    name: column.name,
+                    tasks: Vec::new(),
+                    sort: column.sort,
+                    wip_limit: column.wip_limit,
+                })
+                .collect();
+            projects.push(Project {
+                id: id.clone(),
+                name: project_row.name,
+                board: Board {
+                    columns,
+                    blocked_gate: project_row.blocked_gate,
+                },
+            });
+            self.projects.insert(id, (position as usize, data));
+        }
+
+        let mut statement = self.conn.prepare(
+            "SELECT project_id, id, column_index, position, data FROM tasks
+             ORDER BY project_id, column_index, position",
+        )?;
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?;
+        for row in rows {
+            let (project_id, id, column, position, data) = row?;
+            let task: Task = from_json(&data)?;
+            let Some(project) = projects.iter_mut().find(|p| p.id == project_id) else {
+                continue; // orphaned row, dropped on the next save
+            };
+            let columns = &mut project.board.columns;
+            if let Some(last) = columns.len().checked_sub(1) {
+                columns[(column as usize).min(last)].tasks.push(task);
+            }
+            self.tasks.insert(
+                (project_id, id),
+                TaskRow {
+                    column: column as usize,
+                    position: position as usize,
+                    data,
+                },
+            );
+        }
+        Ok(projects)
+    }
+
+    fn write_changes(&mut self, projects: &[Project]) -> rusqlite::Result<()> {
+        // Someone else wrote the database: our cache says nothing about it, rewrite everything
+        if storage::file_stamp(&self.path) != self.stamp {
+            self.projects.clear();
+            self.tasks.clear();
+        }
+
+        let mut new_projects = HashMap::new();
+        let mut new_tasks = HashMap::new();
+        for (position, project) in projects.iter().enumerate() {
+            let row = ProjectRow {
+                name: project.name.clone(),
+                columns: project
+                    .board
+                    .columns
+                    .iter()
+                    .map(|column| ColumnRow {
+                        id: column.id.clone(),
+                        name: column.name.clone(),
+                        sort: column.sort,
+                        wip_limit: column.wip_limit,
+                    })
+                    .collect(),
+                blocked_gate: project.board.blocked_gate.clone(),
+            };
+            new_projects.insert(project.id.clone(), (position, to_json(&row)?));
+
+            for (column, board_column) in project.board.columns.iter().enumerate() {
+                for (position, task) in board_column.tasks.iter().enumerate() {
+                    let row = TaskRow {
+                        column,
+                        position,
+                        data: to_json(task)?,
+                    };
+                    new_tasks.insert((project.id.clone(), task.id.clone()), row);
+                }
+            }
+        }
+
+        let tx = self.conn.transaction()?;
+        if self.projects.is_empty() && self.tasks.is_empty() {
+            tx.execute("DELETE FROM tasks", [])?;
+            tx.execute("DELETE FROM projects", [])?;
+        }
+        for id in self
+            .projects
+            .keys()
+            .filter(|id| !new_projects.contains_key(*id))
+        {
+            tx.execute("DELETE FROM tasks WHERE project_id = ?1", params![id])?;
+            tx.execute("DELETE FROM projects WHERE id = ?1", params![id])?;
+        }
+        for (project_id, id) in self
+            .tasks
+            .keys()
+            .filter(|key| !new_tasks.contains_key(*key))
+        {
+            tx.execute(
+                "DELETE FROM tasks WHERE project_id = ?1 AND id = ?2",
+                params![project_id, id],
+            )?;
+        }
+        for (id, (position, data)) in &new_projects {
+            if self.projects.get(id) != Some(&(*position, data.clone())) {
+                tx.execute(
+                    "INSERT OR REPLACE INTO projects (id, position, data) VALUES (?1, ?2, ?3)",
+                    params![id, *position as i64, data],
+                )?;
+            }
+        }
+        for ((project_id, id), row) in &new_tasks {
+            if self.tasks.get(&(project_id.clone(), id.clone())) != Some(row) {
+                tx.execute(
+                    "INSERT OR REPLACE INTO tasks (project_id, id, column_index, position, data)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        project_id,
+                        id,
+                        row.column as i64,
+                        row.position as i64,
+                        row.data
+                    ],
+                )?;
+            }
+        }
+        tx.commit()?;
+
+        self.projects = new_projects;
+        self.tasks = new_tasks;
+        self.stamp = storage::file_stamp(&self.path);
+        Ok(())
+    }
+
+    fn describe(&self, error: rusqlite::Error) -> String {
+        format!("{}: {}", self.path.display(), error)
+    }
+
+    fn is_empty(&self) -> rusqlite::Result<bool> {
+        let project: Option<String> = self
+            .conn
+            .query_row("SELECT id FROM projects LIMIT 1", [], |row| row.get(0))
+            .optional()?;
+        Ok(project.is_none())
+    }
+}
+
+impl Backend for SqliteBackend {
+    fn load(&mut self, read_only: bool) -> Result<Vec<Project>, LoadError> {
+        let empty = self.is_empty().map_err(|e| self.describe(e))?;
+        if empty {
+            // First run on SQLite: bring over the JSON projects, if any
+            let mut projects = self.import.load(read_only)?;
+            storage::backfill_ids(&mut projects);
+            if read_only {
+                return Ok(projects);
+            }
+            self.write_changes(&projects)
+                .map_err(|e| self.describe(e))?;
+            return Ok(projects);
+        }
+        self.reload()
+    }
+
+    fn reload(&mut self) -> Result<Vec<Project>, LoadError> {
+        let mut projects = self.read_all().map_err(|e| self.describe(e))?;
+        self.stamp = storage::file_stamp(&self.path);
+        storage::backfill_ids(&mut projects);
+        Ok(projects)
+    }
+
+    fn save(&mut self, projects: &[Project]) -> Result<(), Box<dyn std::error::Error>> {
+        let (backup_dir, keep) = self.import.backups();
+        storage::backup_before_save(self, &backup_dir, keep)?;
+        self.write_changes(projects)?;
+        Ok(())
+    }
+
+    // JSON snapshots, just like the JSON backend takes
+    fn backup(&mut self, keep: usize) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+        if self.is_empty()? {
+            return Ok(None);
+        }
+        // Reading refreshes the cached rows, so they match the database again
+        let projects = self.read_all()?;
+        self.stamp = storage::file_stamp(&self.path);
+        let content = crate::schema::to_json(&projects)?;
+        let (backup_dir, _) = self.import.backups();
+        Ok(Some(storage::write_backup(&backup_dir, &content, keep)?))
+    }
+
+    fn restore(
+        &mut self,
+        projects: &[Project],
+        keep: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.backup(keep)?;
+        self.write_changes(projects)?;
+        Ok(())
+    }
+
+    fn stamp(&self) -> Option<FileStamp> {
+        storage::file_stamp(&self.path)
+    }
+
+    // only the JSON projects brought over on the first run are moved aside
+    fn pending_corrupt(&self) -> Vec<PathBuf> {
+        self.import.pending_corrupt()
+    }
+
+    fn resolve_corrupt(&mut self) {
+        self.import.resolve_corrupt();
+    }
+}
+
+fn create_schema(conn: &Connection) -> Result<(), String> {
+    create_tables(conn).map_err(|e| e.to_string())?;
+    let version: Option<i64> = conn
+        .query_row(
+            "SELECT value FROM meta WHERE key = 'schema_version'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?;
+    match version {
+        None => {
+            conn.execute(
+                "INSERT INTO meta (key, value) VALUES ('schema_version', ?1)",
+                params![DB_SCHEMA_VERSION],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        Some(version) if version > DB_SCHEMA_VERSION => {
+            return Err(format!(
+                "schema_version {} was written by a newer tui-kanban (this one reads up to {})",
+                version, DB_SCHEMA_VERSION
+            ));
+        }
+        Some(_) => {}
+    }
+    Ok(())
+}
+
+fn create_tables(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value INTEGER NOT NULL);
+         CREATE TABLE IF NOT EXISTS projects (
+             id TEXT PRIMARY KEY,
+             position INTEGER NOT NULL,
+             data TEXT NOT NULL
+         );
+         CREATE TABLE IF NOT EXISTS tasks (
+             project_id TEXT NOT NULL,
+             id TEXT NOT NULL,
+             column_index INTEGER NOT NULL,
+             position INTEGER NOT NULL,
+             data TEXT NOT NULL,
+             PRIMARY KEY (project_id, id)
+         );",
+    )
+}
+
+fn to_json<T: Serialize>(value: &T) -> rusqlite::Result<String> {
+    serde_json::to_string(value).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+fn from_json<T: for<'de> Deserialize<'de>>(data: &str) -> rusqlite::Result<T> {
+    serde_json::from_str(data).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn count_changes(backend: &SqliteBackend) -> u64 {
+        backend.conn.total_changes()
+    }
+
+    #[test]
+    fn test_round_trip_and_incremental_save() {
+        let dir = tempdir().unwrap();
+        let db = dir.path().join("projects.db");
+        let mut backend =
+            SqliteBackend::open(&db, JsonBackend::new(dir.path().join("projects"))).unwrap();
+
+        let mut projects = backend.load(false).unwrap();
+        assert_eq!(projects[0].name, "Default");
+        for i in 0..50 {
+            projects[0].board.columns[0]
+                .tasks
+                .push(Task::new(format!("Task {}", i)));
+        }
+        backend.save(&projects).unwrap();
+
+        // one edit rewrites one row
+        let before = count_changes(&backend);
+        projects[0].board.columns[0].tasks[10].title = "Edited".to_string();
+        backend.save(&projects).unwrap();
+        assert_eq!(count_changes(&backend) - before, 1);
+
+        // deleting a task deletes its row and shifts the ones after it
+        let removed = projects[0].board.columns[0].tasks.remove(48);
+        backend.save(&projects).unwrap();
+
+        let mut reopened =
+            SqliteBackend::open(&db, JsonBackend::new(dir.path().join("projects"))).unwrap();
+        let loaded = reopened.load(false).unwrap();
+        let tasks = &loaded[0].board.columns[0].tasks;
+        assert_eq!(tasks.len(), 49);
+        assert_eq!(tasks[10].title, "Edited");
+        assert_eq!(tasks[48].title, "Task 49");
+        assert!(tasks.iter().all(|task| task.id != removed.id));
+        assert_eq!(loaded[0].id, projects[0].id);
+    }
+
+    #[test]
+    fn test_imports_projects_json() {
+        let dir = tempdir().unwrap();
+        let json = dir.path().join("projects.json");
+        let mut project = Project::new("Imported".to_string());
+        project.board.columns[2]
+            .tasks
+            .push(Task::new("Carried over".to_string()));
+        std::fs::write(&json, crate::schema::to_json(&[project]).unwrap()).unwrap();
+
+        let mut backend = SqliteBackend::open(
+            &dir.path().join("projects.db"),
+            JsonBackend::new(dir.path().join("projects")),
+        )
+        .unwrap();
+        let projects = backend.load(false).unwrap();
+        assert_eq!(projects[0].name, "Imported");
+        assert_eq!(projects[0].board.columns[2].tasks[0].title, "Carried over");
+
+        // once imported, the database is the source of truth
+        let projects = backend.reload().unwrap();
+        assert_eq!(projects[0].board.columns[2].tasks.len(), 1);
+    }
+
+    #[test]
+    fn test_moves_and_project_removal() {
+        let dir = tempdir().unwrap();
+        let db = dir.path().join("projects.db");
+        let mut backend =
+            SqliteBackend::open(&db, JsonBackend::new(dir.path().join("projects"))).unwrap();
+        let mut projects = backend.load(false).unwrap();
+        projects.push(Project::new("Second".to_string()));
+        projects[0].board.columns[0]
+            .tasks
+            .push(Task::new("Moving".to_string()));
+        backend.save(&projects).unwrap();
+
+        let task = projects[0].board.columns[0].tasks.remove(0);
+        projects[0].board.columns[3].tasks.push(task);
+        projects.remove(1);
+        backend.save(&projects).unwrap();
+
+        let loaded = SqliteBackend::open(&db, JsonBackend::new(dir.path().join("projects")))
+            .unwrap()
+            .load(false)
+            .unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert!(loaded[0].board.columns[0].tasks.is_empty());
+        assert_eq!(loaded[0].board.columns[3].tasks[0].title, "Moving");
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let dir = tempdir().unwrap();
+        let db = dir.path().join("projects.db");
+        let mut backend =
+            SqliteBackend::open(&db, JsonBackend::new(dir.path().join("projects"))).unwrap();
+        let mut projects = backend.load(false).unwrap();
+        projects[0].name = "Before".to_string();
+        backend.save(&projects).unwrap();
+
+        let backup = backend.backup(5).unwrap().unwrap();
+        assert!(backup.starts_with(dir.path().join("backups")));
+        let (from_backup, _) =
+            crate::schema::parse_projects(&std::fs::read_to_string(&backup).unwrap()).unwrap();
+        assert_eq!(from_backup[0].name, "Before");
+
+        projects[0].name = "After".to_string();
+        projects.push(Project::new("Extra".to_string()));
+        backend.save(&projects).unwrap();
+
+        backend.restore(&from_backup, 5).unwrap();
+        let loaded = SqliteBackend::open(&db, JsonBackend::new(dir.path().join("projects")))
+            .unwrap()
+            .load(false)
+            .unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].name, "Before");
+    }
+}
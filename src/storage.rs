@@ -1,96 +1,264 @@
-use crate::board::{Board, BoardColumn, Project, Task};
+use crate::board::{InsertPosition, Project};
+use crate::schema::{self, ParseError};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs;
-use std::path::PathBuf;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+
+// backups kept next to the projects unless config says otherwise
+const DEFAULT_BACKUP_COUNT: usize = 10;
+
+// while running, take a new backup at most this often
+const BACKUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+// the first save of every session always takes a backup
+static BACKED_UP_THIS_SESSION: AtomicBool = AtomicBool::new(false);
+
+// folder for config and data given with --data-dir
+static HOME_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+// environment variable naming a folder for config and data
+const HOME_ENV: &str = "TUI_KANBAN_HOME";
+
+// what lives in the data dir; the first three are the projects themselves
+const DATA_FILES: [&str; 4] = ["projects", "projects.json", "projects.db", "backups"];
+
+const LOCK_FILE: &str = "tui-kanban.lock";
 
 // Config struct for storing application settings
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Config {
     pub default_project: Option<String>,
     pub theme: Option<String>,
+    #[serde(default)]
+    pub insert_position: InsertPosition,
+    #[serde(default = "default_backup_count")]
+    pub backup_count: usize, // 0 disables backups
+    #[serde(default)]
+    pub storage: StorageKind,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub todo_txt: Vec<TodoTxtSync>,
 }
 
-// This struct represents the old Board structure for migration purposes
+// a project kept in sync with a todo.txt file
 #[derive(Deserialize, Serialize, Debug, Clone)]
-struct LegacyBoard {
-    pub todo: Vec<Task>,
-    pub in_progress: Vec<Task>,
-    pub testing: Vec<Task>,
-    pub done: Vec<Task>,
-}
-
-// For projects.json (intermediate format)
-#[derive(Deserialize, Serialize)]
-struct LegacyProject {
-    name: String,
-    board: LegacyBoard, // Uses the old board structure
-}
-
-// Conversion logic from LegacyBoard to new Board format
-impl From<LegacyBoard> for Board {
-    fn from(legacy_board: LegacyBoard) -> Self {
-        Board {
-            columns: vec![
-                BoardColumn {
-                    id: "todo".to_string(),
-                    name: "To Do".to_string(),
-                    tasks: legacy_board.todo,
-                },
-                BoardColumn {
-                    id: "in_progress".to_string(),
-                    name: "In Progress".to_string(),
-                    tasks: legacy_board.in_progress,
-                },
-                BoardColumn {
-                    id: "testing".to_string(),
-                    name: "Testing".to_string(),
-                    tasks: legacy_board.testing,
-                },
-                BoardColumn {
-                    id: "done".to_string(),
-                    name: "Done".to_string(),
-                    tasks: legacy_board.done,
-                },
-            ],
-        }
-    }
-}
-
-// Conversion logic from LegacyProject to new Project format
-impl From<LegacyProject> for Project {
-    fn from(legacy_project: LegacyProject) -> Self {
-        Project {
-            name: legacy_project.name,
-            board: legacy_project.board.into(), // Use the From<LegacyBoard> impl
-        }
-    }
-}
-
-// get path to projects file
-fn get_config_path() -> PathBuf {
+pub struct TodoTxtSync {
+    // project id; a name is accepted and replaced with the id on the first sync
+    pub project: String,
+    pub file: PathBuf,
+}
+
+// which backend keeps the projects
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageKind {
+    // projects/ with one JSON file per project
+    #[default]
+    Json,
+    // projects.db, only changed rows are written (needs the `sqlite` feature)
+    Sqlite,
+}
+
+/// why stored projects couldn't be loaded
+#[derive(Debug)]
+pub enum LoadError {
+    /// unreadable or corrupt; saving anyway starts over with what is in memory
+    Failed(String),
+    /// written by a newer tui-kanban: left as it is and never saved over
+    Newer(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Failed(e) | LoadError::Newer(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<String> for LoadError {
+    fn from(e: String) -> Self {
+        LoadError::Failed(e)
+    }
+}
+
+/// where projects are kept
+pub trait Backend {
+    /// load all projects; Err means stored data exists but couldn't be loaded.
+    /// With `read_only` set nothing is written back: no ids filled in, no
+    /// older layout migrated and no corrupt file moved aside
+    fn load(&mut self, read_only: bool) -> Result<Vec<Project>, LoadError>;
+    /// load again after `stamp` changed, without side effects like quarantining
+    fn reload(&mut self) -> Result<Vec<Project>, LoadError>;
+    fn save(&mut self, projects: &[Project]) -> Result<(), Box<dyn std::error::Error>>;
+    /// identifies the stored state, so writes by someone else can be noticed
+    fn stamp(&self) -> Option<FileStamp>;
+    /// files moved aside as corrupt that the user hasn't dealt with yet;
+    /// saving stays blocked until they have
+    fn pending_corrupt(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+    /// the user dealt with them: saved anyway or restored a backup
+    fn resolve_corrupt(&mut self) {}
+    /// copy the stored projects into the backups folder as a single file and
+    /// drop all but the newest `keep`; Ok(None) if nothing is stored yet
+    fn backup(&mut self, keep: usize) -> Result<Option<PathBuf>, Box<dyn std::error::Error>>;
+    /// replace the stored projects with the ones from a backup, backing up
+    /// the current ones first so the restore can be undone
+    fn restore(
+        &mut self,
+        projects: &[Project],
+        keep: usize,
+    ) -> Result<(), Box<dyn std::error::Error>>;
+}
+
+/// one file per project in the data dir
+pub fn default_backend(config: &Config) -> Box<dyn Backend> {
+    Box::new(json_backend(config))
+}
+
+// the projects folder in the data dir, kept as config.json says
+fn json_backend(config: &Config) -> JsonBackend {
+    let mut backend = JsonBackend::new(get_projects_dir());
+    backend.backup_count = config.backup_count;
+    // Migrate from the old omarchy-kanban projects.json or, older still, board.json,
+    // but not into a home picked with --data-dir or TUI_KANBAN_HOME
+    if home_override().is_none() {
+        backend.legacy_files = vec![get_old_omarchy_config_path(), get_old_board_path()];
+    }
+    backend
+}
+
+/// open the backend chosen in config.json
+pub fn open_backend(config: &Config) -> Result<Box<dyn Backend>, String> {
+    match config.storage {
+        StorageKind::Json => Ok(default_backend(config)),
+        #[cfg(feature = "sqlite")]
+        StorageKind::Sqlite => {
+            let db_path = get_data_dir().join("projects.db");
+            let backend = crate::sqlite::SqliteBackend::open(&db_path, json_backend(config))?;
+            Ok(Box::new(backend))
+        }
+        #[cfg(not(feature = "sqlite"))]
+        StorageKind::Sqlite => Err(
+            "config.json asks for \"storage\": \"sqlite\", but this build of tui-kanban was compiled without the sqlite feature."
+                .to_string(),
+        ),
+    }
+}
+
+fn default_backup_count() -> usize {
+    DEFAULT_BACKUP_COUNT
+}
+
+/// use `dir` for config and data instead of the usual locations (--data-dir);
+/// must be called before anything is loaded
+pub fn set_home(dir: PathBuf) {
+    let _ = HOME_OVERRIDE.set(dir);
+}
+
+// --data-dir, else TUI_KANBAN_HOME: config and data both live there
+fn home_override() -> Option<PathBuf> {
+    HOME_OVERRIDE.get().cloned().or_else(|| {
+        std::env::var_os(HOME_ENV)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+// get path to the folder with config.json
+fn get_config_dir() -> PathBuf {
     // ProjectDirs auto find config
-    if let Some(proj_dirs) = ProjectDirs::from("", "", "tui-kanban") {
-        let config_dir = proj_dirs.config_dir();
-        // folder exists?
+    let dir = home_override()
+        .or_else(|| {
+            ProjectDirs::from("", "", "tui-kanban").map(|dirs| dirs.config_dir().to_path_buf())
+        })
+        // fallback
+        .unwrap_or_default();
+    fs::create_dir_all(&dir).ok();
+    dir
+}
 
-        fs::create_dir_all(config_dir).ok();
-        config_dir.join("projects.json")
+// get path to the folder with the projects, backups and lock file
+fn get_data_dir() -> PathBuf {
+    static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+    DATA_DIR
+        .get_or_init(|| {
+            let dir = match (home_override(), ProjectDirs::from("", "", "tui-kanban")) {
+                (Some(dir), _) => dir,
+                (None, Some(dirs)) => data_location(dirs.config_dir(), dirs.data_dir()),
+                // fallback
+                (None, None) => PathBuf::new(),
+            };
+            fs::create_dir_all(&dir).ok();
+            dir
+        })
+        .clone()
+}
+
+// data used to be kept next to config.json: until migrate_data_dir moved it,
+// it is used where it is
+fn data_location(config_dir: &Path, data_dir: &Path) -> PathBuf {
+    let has_data = |dir: &Path| DATA_FILES[..3].iter().any(|name| dir.join(name).exists());
+    if has_data(data_dir) || !has_data(config_dir) {
+        data_dir.to_path_buf()
     } else {
-        // fallback
-        PathBuf::from("projects.json")
+        config_dir.to_path_buf()
     }
 }
 
+/// move data an older version kept next to config.json into the data dir;
+/// called once at startup before anything is loaded, and skipped while
+/// another instance is using the data where it is
+pub fn migrate_data_dir() {
+    if home_override().is_some() {
+        return;
+    }
+    let Some(dirs) = ProjectDirs::from("", "", "tui-kanban") else {
+        return;
+    };
+    let (config_dir, data_dir) = (dirs.config_dir(), dirs.data_dir());
+    if data_location(config_dir, data_dir) == data_dir {
+        return;
+    }
+    let lock_path = config_dir.join(LOCK_FILE);
+    if let Ok(Some(_lock)) = try_lock(&lock_path) {
+        move_data(config_dir, data_dir);
+        // from now on instances lock the data dir
+        let _ = fs::remove_file(&lock_path);
+    }
+}
+
+// move what there is of the data files; if the first can't be moved, e.g. to
+// another file system, the data stays wherever it is now
+fn move_data(config_dir: &Path, data_dir: &Path) {
+    if fs::create_dir_all(data_dir).is_err() {
+        return;
+    }
+    for name in DATA_FILES {
+        let from = config_dir.join(name);
+        if from.exists() && fs::rename(&from, data_dir.join(name)).is_err() {
+            break;
+        }
+    }
+}
+
+// get path to the lock file in the data dir
+pub fn get_lock_path() -> PathBuf {
+    get_data_dir().join(LOCK_FILE)
+}
+
 // get path to config.json file
 fn get_app_config_path() -> PathBuf {
-    if let Some(proj_dirs) = ProjectDirs::from("", "", "tui-kanban") {
-        let config_dir = proj_dirs.config_dir();
-        fs::create_dir_all(config_dir).ok();
-        config_dir.join("config.json")
-    } else {
-        PathBuf::from("config.json")
-    }
+    get_config_dir().join("config.json")
 }
 
 // get old omarchy-kanban config path for migration
@@ -113,67 +281,623 @@ fn get_old_board_path() -> PathBuf {
     }
 }
 
-/// saves projects to disc
-pub fn save_projects(projects: &[Project]) -> Result<(), Box<dyn std::error::Error>> {
-    let path = get_config_path();
-    let json = serde_json::to_string_pretty(projects)?;
-    fs::write(path, json)?;
-    Ok(())
+// get path to the folder with one file per project plus index.json
+fn get_projects_dir() -> PathBuf {
+    get_data_dir().join("projects")
 }
 
-// read projects
-pub fn load_projects() -> Vec<Project> {
-    let path = get_config_path();
-    let old_omarchy_path = get_old_omarchy_config_path();
-    let old_board_path = get_old_board_path();
+// todo.txt files as last synced, to tell which side changed
+pub fn get_todo_txt_dir() -> PathBuf {
+    get_data_dir().join("todo-txt")
+}
 
-    // 1. Try to load projects in the NEW format (main path)
-    if path.exists() {
-        if let Ok(content) = fs::read_to_string(&path) {
-            if let Ok(projects) = serde_json::from_str::<Vec<Project>>(&content) {
-                return projects;
+// get path to the backups folder next to the projects folder
+pub fn get_backup_dir() -> PathBuf {
+    backup_dir_for(&get_projects_dir())
+}
+
+fn backup_dir_for(path: &Path) -> PathBuf {
+    path.parent()
+        .map(|dir| dir.join("backups"))
+        .unwrap_or_else(|| PathBuf::from("backups"))
+}
+
+// order of the project files, kept in index.json
+#[derive(Serialize, Deserialize)]
+struct Index {
+    schema_version: u32,
+    projects: Vec<IndexEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+struct IndexEntry {
+    id: String,
+    file: String,
+}
+
+/// projects kept as JSON, one file per project plus an index.json with their
+/// order, so a single project can be shared, versioned or backed up on its own
+pub struct JsonBackend {
+    dir: PathBuf,
+    // projects.json of the older single-file layout, migrated on first load
+    single_file: PathBuf,
+    // file name of each project by id, as listed in the index
+    files: HashMap<String, String>,
+    // contents of the files as we last read or wrote them, so unchanged
+    // projects aren't rewritten
+    written: HashMap<String, String>,
+    stamp: Option<FileStamp>,
+    // backups kept in the backups folder next to `dir`, 0 for none
+    backup_count: usize,
+    // old omarchy-kanban files to migrate from when there are no projects yet
+    legacy_files: Vec<PathBuf>,
+}
+
+impl JsonBackend {
+    pub fn new(dir: PathBuf) -> Self {
+        let single_file = dir.with_file_name("projects.json");
+        Self {
+            dir,
+            single_file,
+            files: HashMap::new(),
+            written: HashMap::new(),
+            stamp: None,
+            backup_count: DEFAULT_BACKUP_COUNT,
+            legacy_files: Vec::new(),
+        }
+    }
+
+    /// the backups folder and how many backups it keeps
+    pub(crate) fn backups(&self) -> (PathBuf, usize) {
+        (backup_dir_for(&self.dir), self.backup_count)
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.dir.join("index.json")
+    }
+
+    // lists the files moved aside, until the user decides what to do about them
+    fn pending_path(&self) -> PathBuf {
+        self.dir.with_file_name("corrupt-pending")
+    }
+
+    // read the index and every project it lists; with `quarantine` set, a
+    // corrupt file is moved aside before the error is returned
+    fn read_all(&mut self, quarantine: bool) -> Result<Vec<Project>, LoadError> {
+        let pending_path = self.pending_path();
+        let pending = quarantine.then_some(pending_path.as_path());
+        let index_path = self.index_path();
+        let content = read_file(&index_path)?;
+        let index: Index = schema::parse_versioned(&content, "not a tui-kanban index")
+            .map_err(|e| file_error(&index_path, e, pending))?;
+
+        let mut files = HashMap::new();
+        let mut written = HashMap::new();
+        let mut projects = Vec::new();
+        for entry in index.projects {
+            let path = self.dir.join(&entry.file);
+            let content = read_file(&path)?;
+            let project =
+                schema::parse_project(&content).map_err(|e| file_error(&path, e, pending))?;
+            files.insert(project.id.clone(), entry.file.clone());
+            written.insert(entry.file, content);
+            projects.push(project);
+        }
+        written.insert("index.json".to_string(), content);
+
+        self.files = files;
+        self.written = written;
+        self.stamp = self.stamp();
+        Ok(projects)
+    }
+
+    fn write_all(&mut self, projects: &[Project]) -> Result<(), Box<dyn std::error::Error>> {
+        fs::create_dir_all(&self.dir)?;
+
+        // Someone else wrote the files: what we remember about them is stale
+        if self.stamp() != self.stamp {
+            self.written.clear();
+        }
+
+        // Keep each project's file name once it has one
+        let mut used: HashSet<String> = HashSet::new();
+        let mut entries = Vec::new();
+        for project in projects {
+            let file = match self.files.get(&project.id) {
+                Some(file) if !used.contains(file) => file.clone(),
+                _ => unique_file_name(&project.name, &used),
+            };
+            used.insert(file.clone());
+            entries.push(IndexEntry {
+                id: project.id.clone(),
+                file,
+            });
+        }
+
+        for (project, entry) in projects.iter().zip(&entries) {
+            let content = schema::to_project_json(project)?;
+            if self.written.get(&entry.file) != Some(&content) {
+                write_atomic(&self.dir.join(&entry.file), content.as_bytes())?;
+                self.written.insert(entry.file.clone(), content);
+            }
+        }
+
+        // The index goes last: until it's written, the old one still describes
+        // a complete set of files
+        let index = serde_json::to_string_pretty(&Index {
+            schema_version: schema::CURRENT_SCHEMA_VERSION,
+            projects: entries.clone(),
+        })?;
+        if self.written.get("index.json") != Some(&index) {
+            write_atomic(&self.index_path(), index.as_bytes())?;
+            self.written.insert("index.json".to_string(), index);
+        }
+
+        // Files of deleted projects
+        for file in self.files.values().filter(|file| !used.contains(*file)) {
+            let _ = fs::remove_file(self.dir.join(file));
+            self.written.remove(file);
+        }
+        self.files = entries
+            .into_iter()
+            .map(|entry| (entry.id, entry.file))
+            .collect();
+        self.stamp = self.stamp();
+        Ok(())
+    }
+
+    // write the projects, backing up the old ones first
+    fn save_with_backup(&mut self, projects: &[Project]) -> Result<(), Box<dyn std::error::Error>> {
+        let (backup_dir, keep) = self.backups();
+        backup_before_save(self, &backup_dir, keep)?;
+        self.write_all(projects)
+    }
+}
+
+impl Backend for JsonBackend {
+    fn load(&mut self, read_only: bool) -> Result<Vec<Project>, LoadError> {
+        // 1. The per-project layout
+        // A file that exists but can't be read is an error, never a reason to start over
+        if self.index_path().exists() {
+            let mut projects = self.read_all(!read_only)?;
+            if backfill_ids(&mut projects) && !read_only {
+                let _ = self.save_with_backup(&projects);
+            }
+            return Ok(projects);
+        }
+
+        // 2. Split up the single projects.json, or migrate from the old
+        // omarchy-kanban files
+        let pending_path = self.pending_path();
+        let pending = (!read_only).then_some(pending_path.as_path());
+        if let Some(mut projects) =
+            load_single_file(&self.single_file, &self.legacy_files, pending)?
+        {
+            backfill_ids(&mut projects);
+            // The instance that writes does the split
+            if read_only {
+                return Ok(projects);
             }
+            self.write_all(&projects).map_err(|e| e.to_string())?;
+            // Keep the old file among the backups rather than next to the new layout
+            if self.single_file.exists() {
+                let keep = self.backup_count.max(1);
+                backup_file(&self.single_file, &backup_dir_for(&self.dir), keep)
+                    .and_then(|_| fs::remove_file(&self.single_file))
+                    .map_err(|e| e.to_string())?;
+            }
+            return Ok(projects);
+        }
+
+        // 3. Fallback: incase non exist - return default project in NEW format
+        let default_project = Project::new("Default".to_string());
+        Ok(vec![default_project])
+    }
+
+    // unlike load this never moves a file aside, as another instance may be
+    // halfway through writing it
+    fn reload(&mut self) -> Result<Vec<Project>, LoadError> {
+        let mut projects = self.read_all(false).map_err(|e| {
+            let message = |e| format!("Projects changed on disk but can't be loaded: {}", e);
+            match e {
+                LoadError::Failed(e) => LoadError::Failed(message(e)),
+                LoadError::Newer(e) => LoadError::Newer(message(e)),
+            }
+        })?;
+        backfill_ids(&mut projects);
+        Ok(projects)
+    }
+
+    fn save(&mut self, projects: &[Project]) -> Result<(), Box<dyn std::error::Error>> {
+        self.save_with_backup(projects)
+    }
+
+    // snapshot what is on disk now, not what we last loaded
+    fn backup(&mut self, keep: usize) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+        if !self.index_path().exists() {
+            return Ok(None);
+        }
+        let mut on_disk = JsonBackend::new(self.dir.clone());
+        let projects = on_disk.read_all(false)?;
+        let content = schema::to_json(&projects)?;
+        Ok(Some(write_backup(
+            &backup_dir_for(&self.dir),
+            &content,
+            keep,
+        )?))
+    }
+
+    fn restore(
+        &mut self,
+        projects: &[Project],
+        keep: usize,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Learn the current file names so projects missing from the backup are removed
+        if self.index_path().exists() {
+            self.read_all(false)?;
+        }
+        self.backup(keep)?;
+        self.write_all(projects)
+    }
+
+    // index.json plus every project file in the folder
+    fn stamp(&self) -> Option<FileStamp> {
+        let index = file_stamp(&self.index_path())?;
+        let mut paths: Vec<PathBuf> = fs::read_dir(&self.dir)
+            .ok()?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+        let mut files = index.files;
+        for path in paths {
+            if let Some(stamp) = file_stamp(&path) {
+                files.extend(stamp.files);
+            }
+        }
+        Some(FileStamp { files })
+    }
+
+    fn pending_corrupt(&self) -> Vec<PathBuf> {
+        fs::read_to_string(self.pending_path())
+            .unwrap_or_default()
+            .lines()
+            .map(PathBuf::from)
+            // moved back or deleted: nothing left to decide
+            .filter(|path| path.exists())
+            .collect()
+    }
+
+    fn resolve_corrupt(&mut self) {
+        let _ = fs::remove_file(self.pending_path());
+    }
+}
+
+// file name for a new project: its name in lowercase with dashes, made unique
+fn unique_file_name(name: &str, used: &HashSet<String>) -> String {
+    let mut slug = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
         }
     }
+    let slug = match slug.trim_end_matches('-') {
+        "" | "index" => "project".to_string(),
+        slug => slug.to_string(),
+    };
 
-    // 2. Try to migrate from old omarchy-kanban projects.json (intermediate format)
-    if old_omarchy_path.exists() {
-        if let Ok(content) = fs::read_to_string(&old_omarchy_path) {
-            if let Ok(legacy_projects) = serde_json::from_str::<Vec<LegacyProject>>(&content) {
-                let projects: Vec<Project> = legacy_projects.into_iter().map(Into::into).collect();
-                // Save to new location in new format
-                let _ = save_projects(&projects);
-                return projects;
+    let mut file = format!("{}.json", slug);
+    let mut n = 2;
+    while used.contains(&file) {
+        file = format!("{}-{}.json", slug, n);
+        n += 1;
+    }
+    file
+}
+
+fn read_file(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))
+}
+
+// explain a file that doesn't parse; given a `pending` list, it is moved aside
+// first so nothing can overwrite it, and listed there until the user decides
+// about it; a newer version's file is fine and stays put
+fn file_error(path: &Path, error: ParseError, pending: Option<&Path>) -> LoadError {
+    if let ParseError::Newer(_) = error {
+        return LoadError::Newer(format!(
+            "{} can't be opened: {}. Update tui-kanban to use it.",
+            path.display(),
+            error
+        ));
+    }
+    let mut message = format!("{} is corrupt: {}.", path.display(), error);
+    if let Some(pending) = pending {
+        match quarantine(path) {
+            Ok(moved) => {
+                let _ = add_pending(pending, &moved);
+                message.push_str(&format!("\nIt was moved to {}.", moved.display()));
             }
+            Err(e) => message.push_str(&format!("\nIt could not be moved aside: {}.", e)),
+        }
+    }
+    LoadError::Failed(message)
+}
+
+fn add_pending(pending: &Path, moved: &Path) -> io::Result<()> {
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(pending)?;
+    writeln!(file, "{}", moved.display())
+}
+
+// write to a temp file, fsync it and rename it over `path`, so a crash
+// leaves either the old or the new file but never a truncated one
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty());
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no file name"))?;
+    let tmp_path = path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+
+    let mut file = fs::File::create(&tmp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    drop(file);
+    fs::rename(&tmp_path, path)?;
+
+    // fsync the directory so the rename itself survives a crash
+    #[cfg(unix)]
+    if let Ok(dir) = fs::File::open(dir.unwrap_or(Path::new("."))) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
+
+// back up before a save: on the first save of the session, then at most once an hour
+pub(crate) fn backup_before_save(
+    backend: &mut dyn Backend,
+    backup_dir: &Path,
+    keep: usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if keep > 0 && backup_due(backup_dir) && backend.backup(keep)?.is_some() {
+        BACKED_UP_THIS_SESSION.store(true, Ordering::Relaxed);
+    }
+    Ok(())
+}
+
+// should the next save take a backup?
+fn backup_due(backup_dir: &Path) -> bool {
+    if !BACKED_UP_THIS_SESSION.load(Ordering::Relaxed) {
+        return true;
+    }
+    match list_backups_in(backup_dir).first() {
+        Some(newest) => fs::metadata(newest)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_none_or(|age| age >= BACKUP_INTERVAL),
+        None => true,
+    }
+}
+
+// copy `path` into `backup_dir` with a timestamped name and drop all but the
+// newest `keep` backups; returns the new backup, or None if there was nothing to back up
+fn backup_file(path: &Path, backup_dir: &Path, keep: usize) -> io::Result<Option<PathBuf>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)?;
+    write_backup(backup_dir, &content, keep).map(Some)
+}
+
+// write `content` as a new timestamped backup and rotate old ones away
+pub(crate) fn write_backup(backup_dir: &Path, content: &str, keep: usize) -> io::Result<PathBuf> {
+    fs::create_dir_all(backup_dir)?;
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let backup_path = backup_dir.join(format!("projects-{}.json", timestamp));
+    fs::write(&backup_path, content)?;
+
+    for old in list_backups_in(backup_dir).into_iter().skip(keep) {
+        let _ = fs::remove_file(old);
+    }
+    Ok(backup_path)
+}
+
+/// list backups, newest first
+pub fn list_backups() -> Vec<PathBuf> {
+    list_backups_in(&get_backup_dir())
+}
+
+fn list_backups_in(backup_dir: &Path) -> Vec<PathBuf> {
+    let mut backups: Vec<PathBuf> = fs::read_dir(backup_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| is_backup_name(path))
+                .collect()
+        })
+        .unwrap_or_default();
+    // timestamps in the name sort chronologically
+    backups.sort();
+    backups.reverse();
+    backups
+}
+
+fn is_backup_name(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("projects-") && name.ends_with(".json"))
+}
+
+/// restore a backup chosen by number (1 = newest, as printed by `--restore-backup`)
+/// or by file name into `backend`; the current projects are backed up first so
+/// the restore can be undone
+pub fn restore_backup(backend: &mut dyn Backend, selector: &str) -> Result<PathBuf, String> {
+    let backups = list_backups();
+    let backup = find_backup(&backups, selector)
+        .ok_or_else(|| format!("No backup matching '{}'", selector))?;
+    let keep = load_config().backup_count.max(1);
+    restore_backup_file(&backup, backend, keep)?;
+    Ok(backup)
+}
+
+fn find_backup(backups: &[PathBuf], selector: &str) -> Option<PathBuf> {
+    if let Ok(number) = selector.parse::<usize>() {
+        return number
+            .checked_sub(1)
+            .and_then(|idx| backups.get(idx))
+            .cloned();
+    }
+    backups
+        .iter()
+        .find(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name == selector || name.trim_end_matches(".json") == selector)
+        })
+        .cloned()
+}
+
+fn restore_backup_file(
+    backup: &Path,
+    backend: &mut dyn Backend,
+    keep: usize,
+) -> Result<(), String> {
+    let content = fs::read_to_string(backup).map_err(|e| e.to_string())?;
+    // Refuse to restore something we couldn't load afterwards
+    let (mut projects, _) = schema::parse_projects(&content)
+        .map_err(|e| format!("{} is not a valid backup: {}", backup.display(), e))?;
+    backfill_ids(&mut projects);
+
+    backend
+        .restore(&projects, keep)
+        .map_err(|e| e.to_string())?;
+    backend.resolve_corrupt();
+    Ok(())
+}
+
+// backfill ids for projects/tasks saved before ids existed
+pub fn backfill_ids(projects: &mut [Project]) -> bool {
+    let mut changed = false;
+    for project in projects.iter_mut() {
+        changed |= project.ensure_ids();
+    }
+    changed
+}
+
+// read projects from the single-file layout or the old omarchy-kanban files;
+// Ok(None) if there are none, Err if projects.json exists but couldn't be loaded
+fn load_single_file(
+    path: &Path,
+    legacy_files: &[PathBuf],
+    pending: Option<&Path>,
+) -> Result<Option<Vec<Project>>, LoadError> {
+    // A file that exists but can't be read is an error, never a reason to start over
+    if path.exists() {
+        return read_projects_file(path, pending).map(Some);
+    }
+
+    for old_path in legacy_files {
+        if let Some((projects, _)) = fs::read_to_string(old_path)
+            .ok()
+            .and_then(|content| schema::parse_projects(&content).ok())
+        {
+            return Ok(Some(projects));
         }
     }
+    Ok(None)
+}
+
+// read and parse projects.json; given a `pending` list, a file that doesn't
+// parse is moved aside so nothing can overwrite it, and the error says where it went
+fn read_projects_file(path: &Path, pending: Option<&Path>) -> Result<Vec<Project>, LoadError> {
+    let content = read_file(path)?;
+    schema::parse_projects(&content)
+        .map(|(projects, _)| projects)
+        .map_err(|e| file_error(path, e, pending))
+}
+
+/// exclusive lock on the data directory, released when dropped
+/// (or when the process dies, so a crash never leaves a stale lock)
+pub struct DataLock {
+    _file: fs::File,
+}
+
+/// pid of the instance holding the lock at `path`, as it wrote it into the lock file
+pub fn lock_holder_pid(path: &Path) -> Option<u32> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// lock the data directory for this instance through the lock file at
+/// `path`; Ok(None) means another instance already holds it
+pub fn try_lock(path: &Path) -> io::Result<Option<DataLock>> {
+    let mut file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path)?;
+    match file.try_lock() {
+        Ok(()) => {}
+        Err(fs::TryLockError::WouldBlock) => return Ok(None),
+        Err(fs::TryLockError::Error(e)) => return Err(e),
+    }
+
+    // Only the holder writes, so the pid is never clobbered by a second instance
+    file.set_len(0)?;
+    writeln!(file, "{}", std::process::id())?;
+    Ok(Some(DataLock { _file: file }))
+}
 
-    // 3. Try to migrate from old board.json (even older format)
-    if old_board_path.exists() {
-        if let Ok(content) = fs::read_to_string(&old_board_path) {
-            if let Ok(legacy_board) = serde_json::from_str::<LegacyBoard>(&content) {
-                let new_board: Board = legacy_board.into();
-                let default_project = Project {
-                    name: "Default".to_string(),
-                    board: new_board,
-                };
-                // Save as new format
-                let _ = save_projects(&vec![default_project.clone()]);
-                return vec![default_project];
+/// what the data files looked like on disk, to notice when someone else writes them
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileStamp {
+    files: Vec<(SystemTime, u64)>,
+}
+
+impl FileStamp {
+    /// stamp of data spread over two places
+    pub fn combine(a: Option<FileStamp>, b: Option<FileStamp>) -> Option<FileStamp> {
+        match (a, b) {
+            (Some(mut a), Some(b)) => {
+                a.files.extend(b.files);
+                Some(a)
             }
+            (a, b) => a.or(b),
         }
     }
+}
+
+pub fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let meta = fs::metadata(path).ok()?;
+    Some(FileStamp {
+        files: vec![(meta.modified().ok()?, meta.len())],
+    })
+}
 
-    // 4. Fallback: incase non exist - return default project in NEW format
-    let default_project = Project::new("Default".to_string());
-    vec![default_project]
+// rename a corrupt file to <name>.corrupt-<timestamp> next to it
+fn quarantine(path: &Path) -> io::Result<PathBuf> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no file name"))?;
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let moved = path.with_file_name(format!(
+        "{}.corrupt-{}",
+        file_name.to_string_lossy(),
+        timestamp
+    ));
+    fs::rename(path, &moved)?;
+    Ok(moved)
 }
 
 /// save config to disc
 pub fn save_config(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
     let path = get_app_config_path();
     let json = serde_json::to_string_pretty(config)?;
-    fs::write(path, json)?;
+    write_atomic(&path, json.as_bytes())?;
     Ok(())
 }
 
@@ -193,6 +917,10 @@ pub fn load_config() -> Config {
     Config {
         default_project: None,
         theme: Some("high-contrast".to_string()),
+        insert_position: InsertPosition::Bottom,
+        backup_count: DEFAULT_BACKUP_COUNT,
+        storage: StorageKind::Json,
+        todo_txt: Vec::new(),
     }
 }
 
@@ -206,3 +934,318 @@ pub fn get_directory_project() -> Option<String> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn projects_json(name: &str) -> String {
+        serde_json::to_string_pretty(&vec![Project::new(name.to_string())]).unwrap()
+    }
+
+    #[test]
+    fn test_corrupt_file_is_quarantined() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("projects.json");
+        fs::write(&path, "[\n  {\"name\": \"Work\",\n  }\n]").unwrap();
+
+        let error = read_projects_file(&path, Some(&dir.path().join("pending")))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("line 3, column 3"), "{}", error);
+        assert!(!error.contains(" at line"), "{}", error);
+        assert!(!path.exists());
+
+        let moved: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .filter(|name| name != "pending")
+            .collect();
+        assert_eq!(moved.len(), 1);
+        assert!(moved[0].starts_with("projects.json.corrupt-"));
+        assert!(error.contains(&moved[0]));
+    }
+
+    #[test]
+    fn test_file_stamp_changes_on_write() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("projects.json");
+        assert_eq!(file_stamp(&path), None);
+
+        fs::write(&path, "[]").unwrap();
+        let before = file_stamp(&path);
+        assert!(before.is_some());
+        assert_eq!(file_stamp(&path), before);
+
+        write_atomic(&path, b"[ ]").unwrap();
+        assert_ne!(file_stamp(&path), before);
+    }
+
+    #[test]
+    fn test_lock_is_exclusive() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("tui-kanban.lock");
+
+        let lock = try_lock(&path).unwrap();
+        assert!(lock.is_some());
+        assert_eq!(
+            fs::read_to_string(&path).unwrap().trim(),
+            std::process::id().to_string()
+        );
+        assert!(try_lock(&path).unwrap().is_none());
+
+        drop(lock);
+        assert!(try_lock(&path).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_json_backend_round_trip() {
+        let dir = tempdir().unwrap();
+        let mut backend = JsonBackend::new(dir.path().join("projects"));
+        assert_eq!(backend.stamp(), None);
+        assert_eq!(backend.load(false).unwrap()[0].name, "Default");
+
+        let projects = vec![
+            Project::new("Work".to_string()),
+            Project::new("Side Project!".to_string()),
+        ];
+        backend.save(&projects).unwrap();
+        assert!(backend.stamp().is_some());
+        let projects_dir = dir.path().join("projects");
+        assert!(projects_dir.join("work.json").exists());
+        assert!(projects_dir.join("side-project.json").exists());
+
+        let loaded = backend.reload().unwrap();
+        assert_eq!(loaded[0].name, "Work");
+        assert_eq!(loaded[0].id, projects[0].id);
+        assert_eq!(loaded[1].name, "Side Project!");
+    }
+
+    #[test]
+    fn test_json_backend_only_writes_changed_projects() {
+        let dir = tempdir().unwrap();
+        let projects_dir = dir.path().join("projects");
+        let mut backend = JsonBackend::new(projects_dir.clone());
+        let mut projects = vec![
+            Project::new("Work".to_string()),
+            Project::new("Home".to_string()),
+        ];
+        backend.save(&projects).unwrap();
+        let home = file_stamp(&projects_dir.join("home.json"));
+
+        // a rename keeps the file, deleting a project removes it
+        projects[0].name = "Office".to_string();
+        backend.save(&projects).unwrap();
+        assert_eq!(file_stamp(&projects_dir.join("home.json")), home);
+        assert!(projects_dir.join("work.json").exists());
+
+        projects.remove(1);
+        backend.save(&projects).unwrap();
+        assert!(!projects_dir.join("home.json").exists());
+        let loaded = JsonBackend::new(projects_dir).load(false).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].name, "Office");
+    }
+
+    #[test]
+    fn test_single_file_is_migrated() {
+        let dir = tempdir().unwrap();
+        let single_file = dir.path().join("projects.json");
+        fs::write(&single_file, projects_json("Old Layout")).unwrap();
+
+        let mut backend = JsonBackend::new(dir.path().join("projects"));
+        let loaded = backend.load(false).unwrap();
+        assert_eq!(loaded[0].name, "Old Layout");
+        assert!(!single_file.exists());
+        assert!(dir.path().join("projects/old-layout.json").exists());
+        // the old file is kept among the backups
+        assert_eq!(list_backups_in(&dir.path().join("backups")).len(), 1);
+
+        let reloaded = JsonBackend::new(dir.path().join("projects"))
+            .load(false)
+            .unwrap();
+        assert_eq!(reloaded[0].id, loaded[0].id);
+    }
+
+    #[test]
+    fn test_read_only_load_writes_nothing() {
+        let dir = tempdir().unwrap();
+        let single_file = dir.path().join("projects.json");
+        fs::write(&single_file, projects_json("Old Layout")).unwrap();
+
+        let loaded = JsonBackend::new(dir.path().join("projects"))
+            .load(true)
+            .unwrap();
+        assert_eq!(loaded[0].name, "Old Layout");
+        assert!(single_file.exists());
+        assert!(!dir.path().join("projects").exists());
+        assert!(!dir.path().join("backups").exists());
+
+        fs::write(&single_file, "[").unwrap();
+        let mut backend = JsonBackend::new(dir.path().join("projects"));
+        assert!(backend.load(true).is_err());
+        assert!(single_file.exists());
+        assert!(backend.pending_corrupt().is_empty());
+    }
+
+    #[test]
+    fn test_corrupt_project_file_is_quarantined() {
+        let dir = tempdir().unwrap();
+        let projects_dir = dir.path().join("projects");
+        let mut backend = JsonBackend::new(projects_dir.clone());
+        backend.save(&[Project::new("Work".to_string())]).unwrap();
+        fs::write(projects_dir.join("work.json"), "{").unwrap();
+
+        let error = JsonBackend::new(projects_dir.clone())
+            .load(false)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("work.json is corrupt"), "{}", error);
+        assert!(!projects_dir.join("work.json").exists());
+
+        // still undecided on the next start, until it's moved back
+        let mut backend = JsonBackend::new(projects_dir.clone());
+        assert!(backend.load(false).is_err());
+        let pending = backend.pending_corrupt();
+        assert_eq!(pending.len(), 1);
+        fs::rename(&pending[0], projects_dir.join("work.json")).unwrap();
+        assert!(backend.pending_corrupt().is_empty());
+    }
+
+    #[test]
+    fn test_pending_corrupt_until_resolved() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("projects.json"), "[").unwrap();
+        let mut backend = JsonBackend::new(dir.path().join("projects"));
+        assert!(backend.load(false).is_err());
+
+        // the next start finds nothing to load, but the bad file is still waiting
+        let mut backend = JsonBackend::new(dir.path().join("projects"));
+        assert_eq!(backend.load(false).unwrap()[0].name, "Default");
+        assert_eq!(backend.pending_corrupt().len(), 1);
+
+        backend.resolve_corrupt();
+        assert!(backend.pending_corrupt().is_empty());
+    }
+
+    #[test]
+    fn test_newer_files_stay_in_place() {
+        let dir = tempdir().unwrap();
+        let projects_dir = dir.path().join("projects");
+        let mut backend = JsonBackend::new(projects_dir.clone());
+        backend.save(&[Project::new("Work".to_string())]).unwrap();
+        let work = projects_dir.join("work.json");
+        let content = fs::read_to_string(&work).unwrap().replacen(
+            &format!("\"schema_version\": {}", schema::CURRENT_SCHEMA_VERSION),
+            "\"schema_version\": 99",
+            1,
+        );
+        fs::write(&work, &content).unwrap();
+
+        let error = JsonBackend::new(projects_dir.clone())
+            .load(false)
+            .unwrap_err();
+        assert!(matches!(error, LoadError::Newer(_)), "{}", error);
+        assert_eq!(fs::read_to_string(&work).unwrap(), content);
+
+        let single_file = dir.path().join("projects.json");
+        fs::write(&single_file, r#"{"schema_version": 99, "projects": []}"#).unwrap();
+        let error =
+            read_projects_file(&single_file, Some(&dir.path().join("pending"))).unwrap_err();
+        assert!(matches!(error, LoadError::Newer(_)), "{}", error);
+        assert!(single_file.exists());
+    }
+
+    #[test]
+    fn test_data_moves_out_of_config_dir() {
+        let dir = tempdir().unwrap();
+        let config_dir = dir.path().join("config");
+        let data_dir = dir.path().join("data");
+        fs::create_dir_all(config_dir.join("projects")).unwrap();
+        fs::create_dir_all(config_dir.join("backups")).unwrap();
+        fs::write(config_dir.join("config.json"), "{}").unwrap();
+
+        assert_eq!(data_location(&config_dir, &data_dir), config_dir);
+        move_data(&config_dir, &data_dir);
+        assert_eq!(data_location(&config_dir, &data_dir), data_dir);
+        assert!(data_dir.join("projects").is_dir());
+        assert!(data_dir.join("backups").is_dir());
+        assert!(!config_dir.join("projects").exists());
+        assert!(config_dir.join("config.json").exists());
+
+        // new data wins over old data left behind
+        fs::create_dir_all(config_dir.join("projects")).unwrap();
+        assert_eq!(data_location(&config_dir, &data_dir), data_dir);
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("projects.json");
+        fs::write(&path, "old").unwrap();
+
+        write_atomic(&path, b"new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        // no temp file left behind
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_backups_rotate() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("projects.json");
+        let backup_dir = dir.path().join("backups");
+        assert_eq!(backup_file(&path, &backup_dir, 2).unwrap(), None);
+
+        fs::write(&path, "[]").unwrap();
+        for name in ["20260101-000000", "20260102-000000", "20260103-000000"] {
+            fs::create_dir_all(&backup_dir).unwrap();
+            fs::write(backup_dir.join(format!("projects-{}.json", name)), "[]").unwrap();
+        }
+        let newest = backup_file(&path, &backup_dir, 2).unwrap().unwrap();
+
+        let backups = list_backups_in(&backup_dir);
+        assert_eq!(backups.len(), 2);
+        assert_eq!(backups[0], newest);
+        assert!(backups[1].ends_with("projects-20260103-000000.json"));
+    }
+
+    #[test]
+    fn test_restore_backup() {
+        let dir = tempdir().unwrap();
+        let mut backend = JsonBackend::new(dir.path().join("projects"));
+        backend
+            .write_all(&[
+                Project::new("Current".to_string()),
+                Project::new("Extra".to_string()),
+            ])
+            .unwrap();
+        let backup_dir = dir.path().join("backups");
+        fs::create_dir_all(&backup_dir).unwrap();
+        let old = backup_dir.join("projects-20260101-000000.json");
+        fs::write(&old, projects_json("Old")).unwrap();
+
+        let backups = list_backups_in(&backup_dir);
+        assert_eq!(find_backup(&backups, "1"), Some(old.clone()));
+        assert_eq!(
+            find_backup(&backups, "projects-20260101-000000"),
+            Some(old.clone())
+        );
+        assert_eq!(find_backup(&backups, "0"), None);
+
+        let mut backend = JsonBackend::new(dir.path().join("projects"));
+        restore_backup_file(&old, &mut backend, 5).unwrap();
+        let restored = JsonBackend::new(dir.path().join("projects"))
+            .load(false)
+            .unwrap();
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].name, "Old");
+        assert!(!dir.path().join("projects/extra.json").exists());
+        // the replaced projects were backed up too
+        assert_eq!(list_backups_in(&backup_dir).len(), 2);
+
+        fs::write(&old, "not json").unwrap();
+        assert!(restore_backup_file(&old, &mut backend, 5).is_err());
+    }
+}
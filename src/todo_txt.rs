@@ -0,0 +1,639 @@
+//! Two-way sync of projects with todo.txt files, set up in config.json.
+//!
+//! Each task is one line, tagged with the project and with its id so edits
+//! can be matched back to it:
+//!
+//! ```text
+//! (B) 2026-10-01 Fix login @bug +Work due:2026-10-20 status:in_progress id:3f7db5b1fa0e
+//! x 2026-10-12 2026-10-01 Ship it +Work id:016af139cf95
+//! ```
+//!
+//! The file as it was last written is kept in the data dir, so a sync can
+//! tell which side changed a task: lines edited in the file update their
+//! task, new lines add tasks and removed lines remove them. When both sides
+//! changed a task the line wins. Descriptions and checklists stay on the board.
+//!
+//! A sync has two steps: `merge_all` brings the files' changes into the
+//! projects, and `commit_all` writes the projects back to the files once they
+//! are saved. A save that fails leaves the files as they were.
+
+use crate::board::{Board, Priority, Project, Task};
+use crate::storage::{self, TodoTxtSync};
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// one todo.txt line
+#[derive(Debug, Default, PartialEq)]
+struct Line {
+    done: bool,
+    created: Option<NaiveDate>,
+    priority: Priority,
+    title: String,
+    tags: Vec<String>,
+    due: Option<NaiveDate>,
+    // column id or name
+    status: Option<String>,
+    id: Option<String>,
+}
+
+// todo.txt priorities are letters, A the most important
+fn priority_letter(priority: Priority) -> Option<char> {
+    match priority {
+        Priority::None => None,
+        Priority::Urgent => Some('A'),
+        Priority::High => Some('B'),
+        Priority::Medium => Some('C'),
+        Priority::Low => Some('D'),
+    }
+}
+
+fn priority_from_letter(letter: char) -> Priority {
+    match letter {
+        'A' => Priority::Urgent,
+        'B' => Priority::High,
+        'C' => Priority::Medium,
+        _ => Priority::Low,
+    }
+}
+
+// `+Project`, as todo.txt projects can't contain spaces
+fn project_tag(name: &str) -> String {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    format!("+{}", words.join("-"))
+}
+
+fn local_date(time: Option<DateTime<Utc>>) -> Option<NaiveDate> {
+    time.map(|time| time.with_timezone(&Local).date_naive())
+}
+
+// the start of a day in local time
+fn start_of(date: NaiveDate) -> Option<DateTime<Utc>> {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+}
+
+fn task_line(board: &Board, col_idx: usize, task: &Task, project: &str) -> String {
+    let done = board.is_done_column(col_idx);
+    let mut words = Vec::new();
+    if done {
+        words.push("x".to_string());
+        // the completion date can only be given along with the creation date
+        if let (Some(completed), Some(created)) =
+            (local_date(task.completed_at), local_date(task.created_at))
+        {
+            words.push(completed.to_string());
+            words.push(created.to_string());
+        }
+    } else {
+        if let Some(letter) = priority_letter(task.priority) {
+            words.push(format!("({})", letter));
+        }
+        if let Some(created) = local_date(task.created_at) {
+            words.push(created.to_string());
+        }
+    }
+    words.push(task.title.clone());
+    for tag in &task.tags {
+        let tag: Vec<&str> = tag.split_whitespace().collect();
+        words.push(format!("@{}", tag.join("-")));
+    }
+    words.push(project.to_string());
+    if let Some(due) = task.due {
+        words.push(format!("due:{}", due));
+    }
+    if done {
+        // done tasks lose their place at the front of the line
+        if let Some(letter) = priority_letter(task.priority) {
+            words.push(format!("pri:{}", letter));
+        }
+    } else {
+        words.push(format!("status:{}", board.columns[col_idx].id));
+    }
+    words.push(format!("id:{}", task.id));
+    words.join(" ")
+}
+
+/// the project as a todo.txt file, in board order
+pub fn to_todo_txt(project: &Project) -> String {
+    let tag = project_tag(&project.name);
+    let mut out = String::new();
+    for (col_idx, column) in project.board.columns.iter().enumerate() {
+        for task in &column.tasks {
+            out.push_str(&task_line(&project.board, col_idx, task, &tag));
+            out.push('\n');
+        }
+    }
+    out
+}
+
+// a date at the start of `text`, followed by a space or the end
+fn take_date(text: &str) -> Option<(NaiveDate, &str)> {
+    let date = NaiveDate::parse_from_str(text.get(..10)?, "%Y-%m-%d").ok()?;
+    let rest = &text[10..];
+    match rest.strip_prefix(' ') {
+        Some(rest) => Some((date, rest)),
+        None if rest.is_empty() => Some((date, rest)),
+        None => None,
+    }
+}
+
+// `project` is the project's own +tag, which isn't kept in the title
+fn parse_line(text: &str, project: &str) -> Option<Line> {
+    let mut rest = text.trim();
+    if rest.is_empty() {
+        return None;
+    }
+    let mut line = Line::default();
+    if let Some(after) = rest.strip_prefix("x ") {
+        line.done = true;
+        rest = after;
+        // the completion date; the task is completed when the sync sees it
+        if let Some((_, after)) = take_date(rest) {
+            rest = after;
+        }
+    }
+    if let [b'(', letter @ b'A'..=b'Z', b')', b' ', ..] = rest.as_bytes() {
+        line.priority = priority_from_letter(*letter as char);
+        rest = &rest[4..];
+    }
+    if let Some((created, after)) = take_date(rest) {
+        line.created = Some(created);
+        rest = after;
+    }
+
+    let mut title = Vec::new();
+    for word in rest.split_whitespace() {
+        let key_value = word.split_once(':');
+        if let Some(tag) = word.strip_prefix('@').filter(|tag| !tag.is_empty()) {
+            line.tags.push(tag.to_string());
+        } else if word == project {
+            continue;
+        } else if let Some(due) = key_value
+            .filter(|(key, _)| *key == "due")
+            .and_then(|(_, value)| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok())
+        {
+            line.due = Some(due);
+        } else if let Some(("status", value)) = key_value {
+            line.status = Some(value.to_string());
+        } else if let Some(("id", value)) = key_value {
+            line.id = Some(value.to_string());
+        } else if let Some(("pri", value)) = key_value.filter(|(_, value)| value.len() == 1) {
+            line.priority = priority_from_letter(value.chars().next().unwrap_or_default());
+        } else {
+            title.push(word);
+        }
+    }
+    line.title = title.join(" ");
+    Some(line)
+}
+
+// the column a line puts its task in: done lines in the last column, others
+// in their status column, or where the task is, or else the first column
+fn target_column(board: &Board, line: &Line, current: Option<usize>) -> usize {
+    let last = board.columns.len().saturating_sub(1);
+    if line.done {
+        return last;
+    }
+    if let Some(col_idx) = line.status.as_deref().and_then(|s| board.find_column(s)) {
+        return col_idx;
+    }
+    match current {
+        Some(col_idx) if col_idx != last => col_idx,
+        _ => 0,
+    }
+}
+
+// take the fields a line holds over into the task
+fn apply_line(task: &mut Task, line: &Line) {
+    task.title = line.title.clone();
+    task.priority = line.priority;
+    task.tags.clear();
+    for tag in &line.tags {
+        task.add_tag(tag.clone());
+    }
+    task.due = line.due;
+    task.touch();
+}
+
+// move a task into a column, at the bottom
+fn place_task(board: &mut Board, mut task: Task, col_idx: usize) {
+    let is_done = board.is_done_column(col_idx);
+    let column = &mut board.columns[col_idx];
+    task.enter_column(&column.id, is_done);
+    column.tasks.push(task);
+}
+
+// bring the changes made to the file since `base` into the project; returns
+// whether the project changed
+fn merge_file(project: &mut Project, content: &str, base: &str) -> bool {
+    let tag = project_tag(&project.name);
+    let base_lines: HashMap<String, &str> = base
+        .lines()
+        .filter_map(|text| Some((parse_line(text, &tag)?.id?, text.trim())))
+        .collect();
+    // lines without an id yet, already merged when the base is a file that
+    // was merged but couldn't be written back
+    let base_texts: HashSet<&str> = base.lines().map(str::trim).collect();
+    let board = &mut project.board;
+    if board.columns.is_empty() {
+        return false;
+    }
+
+    let mut seen = HashSet::new();
+    let mut changed = false;
+    for text in content.lines() {
+        let Some(line) = parse_line(text, &tag) else {
+            continue;
+        };
+        let id = line.id.clone().filter(|id| seen.insert(id.clone()));
+        match (id, line.id.as_deref().and_then(|id| board.find_task(id))) {
+            (Some(id), Some((col_idx, task_idx))) => {
+                if base_lines.get(&id) == Some(&text.trim()) {
+                    continue;
+                }
+                let target = target_column(board, &line, Some(col_idx));
+                if target == col_idx {
+                    apply_line(&mut board.columns[col_idx].tasks[task_idx], &line);
+                } else {
+                    let mut task = board.columns[col_idx].tasks.remove(task_idx);
+                    apply_line(&mut task, &line);
+                    place_task(board, task, target);
+                }
+                changed = true;
+            }
+            // deleted on the board since the last sync
+            (Some(id), None) if base_lines.contains_key(&id) => {}
+            (None, _) if line.id.is_none() && base_texts.contains(text.trim()) => {}
+            _ => {
+                let mut task = Task::new(String::new());
+                apply_line(&mut task, &line);
+                if let Some(created) = line.created.and_then(start_of) {
+                    task.created_at = Some(created);
+                }
+                let target = target_column(board, &line, None);
+                place_task(board, task, target);
+                changed = true;
+            }
+        }
+    }
+
+    // lines removed from the file
+    for id in base_lines.keys().filter(|id| !seen.contains(*id)) {
+        if let Some((col_idx, task_idx)) = board.find_task(id) {
+            board.columns[col_idx].tasks.remove(task_idx);
+            board.remove_task_references(id);
+            changed = true;
+        }
+    }
+    changed
+}
+
+fn read_optional(path: &Path) -> Result<Option<String>, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("could not read {}: {}", path.display(), e)),
+    }
+}
+
+fn write_if_changed(path: &Path, current: Option<&str>, content: &str) -> Result<(), String> {
+    if current == Some(content) {
+        return Ok(());
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("could not create {}: {}", dir.display(), e))?;
+    }
+    storage::write_atomic(path, content.as_bytes())
+        .map_err(|e| format!("could not write {}: {}", path.display(), e))
+}
+
+/// a todo.txt file merged into its project, to be written back by
+/// `commit_all` once the projects are saved
+#[derive(Debug, Clone)]
+pub struct Merged {
+    project_id: String,
+    file: PathBuf,
+    base: PathBuf,
+    // the file as it was merged, None if there was none
+    content: Option<String>,
+}
+
+/// bring the changes made to `file` since `base` into the project, writing
+/// nothing; `unsaved` is a previous merge of the file that was never written
+/// back, which then stands in for `base`. Returns whether the project changed
+pub fn merge(
+    project: &mut Project,
+    file: &Path,
+    base: &Path,
+    unsaved: Option<&Merged>,
+) -> Result<(bool, Merged), String> {
+    let content = read_optional(file)?;
+    let base_content = match unsaved {
+        Some(merged) => merged.content.clone(),
+        None => read_optional(base)?,
+    };
+    // A missing file is written again rather than taken as every task removed
+    let changed = match &content {
+        Some(content) if Some(content) != base_content.as_ref() => merge_file(
+            project,
+            content,
+            base_content.as_deref().unwrap_or_default(),
+        ),
+        _ => false,
+    };
+    let merged = Merged {
+        project_id: project.id.clone(),
+        file: file.to_path_buf(),
+        base: base.to_path_buf(),
+        content,
+    };
+    Ok((changed, merged))
+}
+
+/// write the saved project to its file and to the base the next merge starts from
+pub fn commit(project: &Project, merged: &Merged) -> Result<(), String> {
+    let current = read_optional(&merged.file)?;
+    let base_content = read_optional(&merged.base)?;
+    if current != merged.content {
+        // Edited again since the merge: the next merge picks that up, starting
+        // from the file as it was merged
+        if let Some(content) = &merged.content {
+            write_if_changed(&merged.base, base_content.as_deref(), content)?;
+        }
+        return Ok(());
+    }
+    let output = to_todo_txt(project);
+    write_if_changed(&merged.file, current.as_deref(), &output)?;
+    write_if_changed(&merged.base, base_content.as_deref(), &output)
+}
+
+// `~/todo.txt` is in the home directory
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), directories::BaseDirs::new()) {
+        (Ok(rest), Some(dirs)) => dirs.home_dir().join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// what `merge_all` did
+#[derive(Debug, Default)]
+pub struct Synced {
+    /// a project changed
+    pub changed: bool,
+    /// an entry named its project and now gives its id: config.json needs saving
+    pub config_changed: bool,
+    /// entries that were skipped, e.g. for a project that no longer exists
+    pub warnings: Vec<String>,
+    /// the files merged, to pass to `commit_all` after saving
+    pub merged: Vec<Merged>,
+}
+
+/// merge every todo.txt file in config.json into its project, with the last
+/// synced contents kept in `base_dir`; `unsaved` are the merges of a previous
+/// call that were never committed. An entry that can't be synced is skipped
+/// with a warning rather than stopping the others
+pub fn merge_all(
+    projects: &mut [Project],
+    syncs: &mut [TodoTxtSync],
+    base_dir: &Path,
+    unsaved: &[Merged],
+) -> Synced {
+    let mut synced = Synced::default();
+    for sync_config in syncs.iter_mut() {
+        // by id, or by name for entries written by hand
+        let found = projects
+            .iter()
+            .position(|p| p.id == sync_config.project)
+            .or_else(|| projects.iter().position(|p| p.name == sync_config.project));
+        let Some(index) = found else {
+            synced.warnings.push(format!(
+                "todo.txt: no project '{}', skipped {}",
+                sync_config.project,
+                sync_config.file.display()
+            ));
+            continue;
+        };
+        let project = &mut projects[index];
+        // so renaming the project doesn't break the sync
+        if sync_config.project != project.id {
+            sync_config.project = project.id.clone();
+            synced.config_changed = true;
+        }
+        let base = base_dir.join(format!("{}.txt", project.id));
+        let file = expand_home(&sync_config.file);
+        let previous = unsaved.iter().find(|merged| merged.file == file);
+        match merge(project, &file, &base, previous) {
+            Ok((changed, merged)) => {
+                synced.changed |= changed;
+                synced.merged.push(merged);
+            }
+            Err(e) => synced
+                .warnings
+                .push(format!("todo.txt sync of '{}' failed: {}", project.name, e)),
+        }
+    }
+    synced
+}
+
+/// write the saved projects back to the files `merge_all` merged; returns
+/// warnings for those that couldn't be written
+pub fn commit_all(projects: &[Project], merged: &[Merged]) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for merged in merged {
+        let Some(project) = projects.iter().find(|p| p.id == merged.project_id) else {
+            continue;
+        };
+        if let Err(e) = commit(project, merged) {
+            warnings.push(format!("todo.txt sync of '{}' failed: {}", project.name, e));
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    // merge and write back right away, as when the save succeeds
+    fn sync(project: &mut Project, file: &Path, base: &Path) -> Result<bool, String> {
+        let (changed, merged) = merge(project, file, base, None)?;
+        commit(project, &merged)?;
+        Ok(changed)
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let line = parse_line(
+            "(B) 2026-10-01 Fix login @bug +Work +Other due:2026-10-20 status:testing id:abc http://x.io",
+            "+Work",
+        )
+        .unwrap();
+        assert_eq!(line.priority, Priority::High);
+        assert_eq!(line.created, NaiveDate::from_ymd_opt(2026, 10, 1));
+        assert_eq!(line.title, "Fix login +Other http://x.io");
+        assert_eq!(line.tags, vec!["bug"]);
+        assert_eq!(line.due, NaiveDate::from_ymd_opt(2026, 10, 20));
+        assert_eq!(line.status.as_deref(), Some("testing"));
+        assert_eq!(line.id.as_deref(), Some("abc"));
+
+        let done = parse_line("x 2026-10-12 2026-10-01 Ship pri:A", "+Work").unwrap();
+        assert!(done.done);
+        assert_eq!(done.created, NaiveDate::from_ymd_opt(2026, 10, 1));
+        assert_eq!(done.priority, Priority::Urgent);
+        assert_eq!(done.title, "Ship");
+        assert_eq!(parse_line("   ", "+Work"), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut project = Project::new("My Work".to_string());
+        let mut task = Task::new("Fix login".to_string());
+        task.priority = Priority::High;
+        task.add_tag("needs review".to_string());
+        task.due = NaiveDate::from_ymd_opt(2026, 10, 20);
+        project.board.columns[1].tasks.push(task);
+        let mut shipped = Task::new("Ship".to_string());
+        shipped.enter_column("done", true);
+        project.board.columns[3].tasks.push(shipped);
+
+        let content = to_todo_txt(&project);
+        let lines: Vec<&str> = content.lines().collect();
+        assert!(lines[0].starts_with("(B) "), "{}", lines[0]);
+        assert!(
+            lines[0].contains("Fix login @needs-review +My-Work due:2026-10-20 status:in_progress"),
+            "{}",
+            lines[0]
+        );
+        assert!(lines[1].starts_with("x "), "{}", lines[1]);
+
+        let parsed = parse_line(lines[0], "+My-Work").unwrap();
+        assert_eq!(parsed.title, "Fix login");
+        assert_eq!(parsed.priority, Priority::High);
+        assert_eq!(parsed.status.as_deref(), Some("in_progress"));
+    }
+
+    #[test]
+    fn test_sync_both_ways() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("todo.txt");
+        let base = dir.path().join("base/todo.txt");
+        let mut project = Project::new("Work".to_string());
+        project.board.columns[0]
+            .tasks
+            .push(Task::new("Keep".to_string()));
+        project.board.columns[0]
+            .tasks
+            .push(Task::new("Remove in file".to_string()));
+        project.board.columns[0]
+            .tasks
+            .push(Task::new("Finish in file".to_string()));
+
+        // the first sync writes the file
+        assert!(!sync(&mut project, &file, &base).unwrap());
+        let content = fs::read_to_string(&file).unwrap();
+        assert_eq!(content.lines().count(), 3);
+
+        // edit the file: finish one, remove one, add one
+        let edited: Vec<String> = content
+            .lines()
+            .filter(|line| !line.contains("Remove in file"))
+            .map(|line| match line.contains("Finish in file") {
+                true => format!("x {}", line),
+                false => line.to_string(),
+            })
+            .chain(["(A) New from phone @errand status:testing".to_string()])
+            .collect();
+        fs::write(&file, edited.join("\n")).unwrap();
+        // meanwhile the board renames a task
+        project.board.columns[0].tasks[0].title = "Keep it".to_string();
+
+        assert!(sync(&mut project, &file, &base).unwrap());
+        let board = &project.board;
+        let titles: Vec<&str> = board.columns[0]
+            .tasks
+            .iter()
+            .map(|t| t.title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["Keep it"]);
+        let new = &board.columns[2].tasks[0];
+        assert_eq!(new.title, "New from phone");
+        assert_eq!(new.priority, Priority::Urgent);
+        assert_eq!(new.tags, vec!["errand"]);
+        assert_eq!(board.columns[3].tasks[0].title, "Finish in file");
+        assert!(board.columns[3].tasks[0].completed_at.is_some());
+
+        // the file now has the board's side too, with ids for the new task
+        let content = fs::read_to_string(&file).unwrap();
+        assert!(content.contains("Keep it +Work"), "{}", content);
+        assert!(content.contains(&format!("id:{}", new.id)), "{}", content);
+        assert_eq!(fs::read_to_string(&base).unwrap(), content);
+
+        // nothing changed on either side: nothing to do
+        assert!(!sync(&mut project, &file, &base).unwrap());
+    }
+
+    #[test]
+    fn test_sync_all_skips_missing_projects() {
+        let dir = tempdir().unwrap();
+        let mut projects = vec![Project::new("Work".to_string())];
+        let mut syncs = vec![
+            TodoTxtSync {
+                project: "Gone".to_string(),
+                file: dir.path().join("gone.txt"),
+            },
+            TodoTxtSync {
+                project: "Work".to_string(),
+                file: dir.path().join("work.txt"),
+            },
+        ];
+
+        let base_dir = dir.path().join("todo-txt");
+        let synced = merge_all(&mut projects, &mut syncs, &base_dir, &[]);
+        assert_eq!(synced.warnings.len(), 1, "{:?}", synced.warnings);
+        assert!(synced.warnings[0].contains("'Gone'"));
+        assert!(commit_all(&projects, &synced.merged).is_empty());
+        assert!(dir.path().join("work.txt").exists());
+        // the entry now names the project by id, which survives a rename
+        assert!(synced.config_changed);
+        assert_eq!(syncs[1].project, projects[0].id);
+        projects[0].name = "Office".to_string();
+        let synced = merge_all(&mut projects, &mut syncs[1..], &base_dir, &[]);
+        assert!(synced.warnings.is_empty());
+        assert!(!synced.config_changed);
+    }
+
+    #[test]
+    fn test_merge_waits_for_commit() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("todo.txt");
+        let base = dir.path().join("base/todo.txt");
+        let mut project = Project::new("Work".to_string());
+        fs::write(&file, "Call the bank\n").unwrap();
+
+        let (changed, merged) = merge(&mut project, &file, &base, None).unwrap();
+        assert!(changed);
+        // not saved yet: nothing is written
+        assert_eq!(fs::read_to_string(&file).unwrap(), "Call the bank\n");
+        assert!(!base.exists());
+
+        // merged again before a save: the line isn't added twice
+        let (changed, merged) = merge(&mut project, &file, &base, Some(&merged)).unwrap();
+        assert!(!changed);
+        fs::write(&file, "Call the bank\nPay rent\n").unwrap();
+        let (changed, merged) = merge(&mut project, &file, &base, Some(&merged)).unwrap();
+        assert!(changed);
+        assert_eq!(project.count_tasks(), 2);
+
+        commit(&project, &merged).unwrap();
+        let content = fs::read_to_string(&file).unwrap();
+        assert_eq!(content.matches("id:").count(), 2, "{}", content);
+        assert_eq!(fs::read_to_string(&base).unwrap(), content);
+    }
+}
@@ -1,7 +1,8 @@
-use crate::app::{App, InputMode};
-use crate::board::{BoardColumn, Task};
+use crate::app::{App, InputMode, LinkKind};
+use crate::board::{BoardColumn, ColumnSort, DueStatus, Priority, Task};
+use crate::dates;
+use chrono::{DateTime, Local, Utc};
 use ratatui::widgets::Clear;
-// Removed Board as it's not directly used here
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -17,7 +18,12 @@ static EMPTY_TASK_VEC: Vec<Task> = Vec::new();
 pub fn draw(f: &mut Frame, app: &mut App) {
     // check if we're in a special view mode
     match app.input_mode {
-        InputMode::ViewingTask | InputMode::EditingTitle | InputMode::EditingDescription => {
+        InputMode::ViewingTask
+        | InputMode::EditingTitle
+        | InputMode::EditingDueDate
+        | InputMode::EditingDescription
+        | InputMode::AddingChecklistItem
+        | InputMode::AddingBlocker => {
             draw_task_detail(f, app);
             return;
         }
@@ -62,6 +68,14 @@ pub fn draw(f: &mut Frame, app: &mut App) {
     if app.input_mode == InputMode::ShowErrorInfo {
         draw_error_popup(f, app);
     }
+
+    if app.input_mode == InputMode::ConfirmingWipLimit {
+        draw_wip_limit_confirmation(f, app);
+    }
+
+    if app.input_mode == InputMode::ConfirmingReload {
+        draw_reload_conflict(f, app);
+    }
 }
 
 // draw header with f and app (immutable)
@@ -143,8 +157,12 @@ fn draw_column(
 ) {
     let is_selected_column = app.selected_column == column_idx;
 
-    // highlight border if selected column
-    let border_style = if is_selected_column {
+    // highlight border if selected column, danger color if over the WIP limit
+    let border_style = if board_column.is_over_wip_limit() {
+        Style::default()
+            .fg(app.theme.danger)
+            .add_modifier(Modifier::BOLD)
+    } else if is_selected_column {
         Style::default()
             .fg(app.theme.border_focused)
             .add_modifier(Modifier::BOLD)
@@ -152,10 +170,27 @@ fn draw_column(
         Style::default().fg(app.theme.border_normal)
     };
 
+    let mut title = match board_column.wip_limit {
+        Some(limit) => format!(
+            "{} ({}/{})",
+            board_column.name,
+            board_column.tasks.len(),
+            limit
+        ),
+        None => board_column.name.clone(),
+    };
+    if board_column.sort == ColumnSort::Priority {
+        title.push_str(" [by priority]");
+    }
+    // mark the column blocked tasks may not move past, when one is configured
+    if app.board().blocked_gate.is_some() && app.board().blocked_gate_index() == column_idx {
+        title.push_str(" [gate]");
+    }
+
     let outer_block = Block::default()
         .borders(Borders::ALL)
         .border_style(border_style)
-        .title(board_column.name.as_str()); // Use board_column.name
+        .title(title);
 
     let inner_area = outer_block.inner(area);
     f.render_widget(outer_block, area);
@@ -176,9 +211,12 @@ fn draw_column(
     // now get the tasks
     let tasks = &board_column.tasks;
 
+    // due dates don't matter once a task is done
+    let is_done_column = app.board().is_done_column(column_idx);
+    let today = dates::today();
+
     // render each task as a card, starting from scroll_offset
-    let mut rendered = 0;
-    for (i, task) in tasks.iter().enumerate().skip(scroll_offset) {
+    for (rendered, (i, task)) in tasks.iter().enumerate().skip(scroll_offset).enumerate() {
         let y_offset = rendered as u16 * (card_height + card_spacing);
 
         // stop if we run out of space
@@ -193,27 +231,48 @@ fn draw_column(
             height: card_height,
         };
 
+        let due_status = if is_done_column {
+            DueStatus::NoDueDate
+        } else {
+            task.due_status(today)
+        };
+
         draw_task_card(
             f,
             app,
             task,
             card_area,
             is_selected_column && i == app.selected_index,
+            due_status,
+            app.board().is_blocked(task),
         );
-        rendered += 1;
     }
 }
 
 /// draw a single task card
-fn draw_task_card(f: &mut Frame, app: &App, task: &Task, area: Rect, is_selected: bool) {
-    // Changed crate::board::Task to Task
+fn draw_task_card(
+    f: &mut Frame,
+    app: &App,
+    task: &Task,
+    area: Rect,
+    is_selected: bool,
+    due_status: DueStatus,
+    is_blocked: bool,
+) {
+    // color used to flag overdue / due soon tasks
+    let due_color = match due_status {
+        DueStatus::Overdue => Some(app.theme.danger),
+        DueStatus::DueSoon => Some(app.theme.accent),
+        DueStatus::NoDueDate | DueStatus::Later => None,
+    };
+
     // card border style
     let border_style = if is_selected {
         Style::default()
-            .fg(app.theme.border_focused)
+            .fg(due_color.unwrap_or(app.theme.border_focused))
             .add_modifier(Modifier::BOLD)
     } else {
-        Style::default().fg(app.theme.border_normal)
+        Style::default().fg(due_color.unwrap_or(app.theme.border_normal))
     };
 
     // background color for selected task
@@ -229,26 +288,55 @@ fn draw_task_card(f: &mut Frame, app: &App, task: &Task, area: Rect, is_selected
         .style(Style::default().bg(bg_color));
 
     let inner = card_block.inner(area);
-    f.render_widget(card_block, inner); // Changed from card_block.inner(area) to area as inner is already calculated
+    f.render_widget(card_block, area);
 
     // render task title and tags on separate lines
     if inner.height >= 2 {
+        // blocked and priority markers in front of the title
+        let blocked_marker = if is_blocked { "⊘ " } else { "" };
+        let marker = if task.priority == Priority::None {
+            String::new()
+        } else {
+            format!("{} ", task.priority.marker())
+        };
+
         // truncate title to fit width
-        let max_title_len = inner.width as usize;
+        let max_title_len = (inner.width as usize)
+            .saturating_sub(blocked_marker.chars().count() + marker.chars().count());
         let truncated_title: String = task.title.chars().take(max_title_len).collect();
 
+        // blocked tasks are dimmed
+        let mut title_modifier = if is_selected {
+            Modifier::BOLD
+        } else {
+            Modifier::empty()
+        };
+        if is_blocked {
+            title_modifier |= Modifier::DIM;
+        }
+
         let mut lines = vec![
-            // Line 1: Title
-            Line::from(Span::styled(
-                truncated_title,
-                Style::default()
-                    .fg(app.theme.text_primary)
-                    .add_modifier(if is_selected {
-                        Modifier::BOLD
-                    } else {
-                        Modifier::empty()
-                    }),
-            )),
+            // Line 1: Blocked + Priority + Title
+            Line::from(vec![
+                Span::styled(
+                    blocked_marker,
+                    Style::default()
+                        .fg(app.theme.danger)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    marker,
+                    Style::default()
+                        .fg(priority_color(app, task.priority))
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    truncated_title,
+                    Style::default()
+                        .fg(app.theme.text_primary)
+                        .add_modifier(title_modifier),
+                ),
+            ]),
         ];
 
         // Line 2: Tags (if any) - each tag with its own color
@@ -265,16 +353,79 @@ fn draw_task_card(f: &mut Frame, app: &App, task: &Task, area: Rect, is_selected
             lines.push(Line::from(tag_spans));
         }
 
+        // Line 3: Checklist progress and due date (if any)
+        let mut info_spans = vec![];
+        let (done, total) = task.checklist_progress();
+        if total > 0 {
+            let color = if done == total {
+                app.theme.success
+            } else {
+                app.theme.text_secondary
+            };
+            info_spans.push(Span::styled(
+                format!("☑ {}/{} ", done, total),
+                Style::default().fg(color),
+            ));
+        }
+        if let Some(due) = task.due {
+            let label = match due_status {
+                DueStatus::Overdue => format!("overdue {}", due.format("%Y-%m-%d")),
+                _ => format!("due {}", due.format("%Y-%m-%d")),
+            };
+            let style = match due_color {
+                Some(color) => Style::default().fg(color).add_modifier(Modifier::BOLD),
+                None => Style::default().fg(app.theme.text_secondary),
+            };
+            info_spans.push(Span::styled(label, style));
+        }
+        if !info_spans.is_empty() {
+            lines.push(Line::from(info_spans));
+        }
+
         let content = Paragraph::new(lines);
         f.render_widget(content, inner);
     }
 }
 
+// color for a priority marker
+fn priority_color(app: &App, priority: Priority) -> Color {
+    match priority {
+        Priority::Urgent => app.theme.danger,
+        Priority::High => app.theme.accent,
+        Priority::Medium => app.theme.primary,
+        Priority::Low | Priority::None => app.theme.text_secondary,
+    }
+}
+
 // draw footer with help text or input field
 fn draw_footer(f: &mut Frame, app: &mut App, area: Rect) {
     let text = match app.input_mode {
         InputMode::Normal => {
-            vec![Line::from(vec![
+            let mut spans = Vec::new();
+            if app.read_only {
+                spans.push(Span::styled(
+                    "READ-ONLY (open elsewhere, Ctrl+s to retry) | ",
+                    Style::default()
+                        .fg(app.theme.danger)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            } else if app.saving_blocked {
+                spans.push(Span::styled(
+                    "NOT SAVING (Ctrl+s to enable) | ",
+                    Style::default()
+                        .fg(app.theme.danger)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            if let Some(message) = &app.status_message {
+                spans.push(Span::styled(
+                    format!("{} | ", message),
+                    Style::default()
+                        .fg(app.theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            spans.extend([
                 Span::raw("hjkl/arrows: navigate | "),
                 Span::raw("Enter: open task | "),
                 Span::raw("a: add task | "),
@@ -282,9 +433,14 @@ fn draw_footer(f: &mut Frame, app: &mut App, area: Rect) {
                 Span::raw("m: move task forward | "),
                 Span::raw("n: move task back | "),
                 Span::raw("d: delete task | "),
+                Span::raw("+/-: priority | "),
+                Span::raw("J/K: reorder | "),
+                Span::raw(format!("i: insert at {} | ", app.insert_position.label())),
+                Span::raw("u/Ctrl+r: undo/redo | "),
                 Span::raw("?: help | "),
                 Span::raw("q: quit"),
-            ])]
+            ]);
+            vec![Line::from(spans)]
         }
         InputMode::AddingTask => {
             vec![
@@ -322,6 +478,15 @@ fn draw_footer(f: &mut Frame, app: &mut App, area: Rect) {
                 Line::from("Press Enter to submit, Esc to cancel"),
             ]
         }
+        InputMode::SettingWipLimit => {
+            vec![
+                Line::from(vec![
+                    Span::styled("WIP Limit: ", Style::default().fg(app.theme.accent)),
+                    Span::raw(&app.input_buffer),
+                ]),
+                Line::from("Press Enter to submit (empty or 0 removes the limit), Esc to cancel"),
+            ]
+        }
         _ => vec![Line::from("")],
     };
 
@@ -347,11 +512,25 @@ fn draw_task_detail(f: &mut Frame, app: &mut App) {
 
     // check what editing mode we're in
     let is_editing_title = app.input_mode == InputMode::EditingTitle;
+    let is_editing_due_date = app.input_mode == InputMode::EditingDueDate;
     let is_editing_description = app.input_mode == InputMode::EditingDescription;
+    let is_adding_checklist_item = app.input_mode == InputMode::AddingChecklistItem;
+    let is_adding_blocker = app.input_mode == InputMode::AddingBlocker;
+    let is_editing = is_editing_title
+        || is_editing_due_date
+        || is_editing_description
+        || is_adding_checklist_item
+        || is_adding_blocker;
 
     // create main container with context-aware title
     let title = if is_editing_title {
         " Task Details - EDITING TITLE (Enter to save, Esc to cancel, Ctrl+e: external-editor) "
+    } else if is_editing_due_date {
+        " Task Details - EDITING DUE DATE (Enter to save, empty to clear, Esc to cancel) "
+    } else if is_adding_checklist_item {
+        " Task Details - ADDING CHECKLIST ITEM (Enter to save, Esc to cancel, Ctrl+e: external-editor) "
+    } else if is_adding_blocker {
+        " Task Details - ADDING BLOCKER (task title or id, Enter to save, Esc to cancel) "
     } else if is_editing_description {
         " Task Details - EDITING DESCRIPTION (Enter for newline, Esc to save, Ctrl+e: external-editor) "
     } else {
@@ -366,20 +545,32 @@ fn draw_task_detail(f: &mut Frame, app: &mut App) {
     let inner = block.inner(area);
     f.render_widget(block, area);
 
+    // checklist grows with its items (up to 10 visible) plus the input line when adding
+    let checklist_height = (task.checklist.len().clamp(1, 10) + 2) as u16
+        + if is_adding_checklist_item { 1 } else { 0 };
+
+    // dependencies list both directions (up to 6 visible) plus the input line when adding
+    let links = app.task_links();
+    let dependencies_height =
+        (links.len().clamp(1, 6) + 2) as u16 + if is_adding_blocker { 1 } else { 0 };
+
     // split into sections
     let sections = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3),  // Title
-            Constraint::Length(12), // Tags (enough for header + up to 9 tags)
-            Constraint::Min(5),     // Description
+            Constraint::Length(3),                   // Title
+            Constraint::Length(12),                  // Tags (enough for header + up to 9 tags)
+            Constraint::Length(3),                   // Due date
+            Constraint::Length(6),                   // Dates
+            Constraint::Min(5),                      // Description
+            Constraint::Length(checklist_height),    // Checklist
+            Constraint::Length(dependencies_height), // Dependencies
         ])
         .split(inner);
 
     // title section - show editable input if editing, otherwise show read-only
     use crate::app::TaskField;
-    let is_title_focused =
-        app.focused_field == TaskField::Title && !is_editing_title && !is_editing_description;
+    let is_title_focused = app.focused_field == TaskField::Title && !is_editing;
 
     if is_editing_title {
         let title_para = Paragraph::new(app.input_buffer.as_str())
@@ -400,6 +591,16 @@ fn draw_task_detail(f: &mut Frame, app: &mut App) {
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw(&task.title),
+            Span::styled(
+                "   Priority: ",
+                Style::default()
+                    .fg(app.theme.primary)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                task.priority.label(),
+                Style::default().fg(priority_color(app, task.priority)),
+            ),
         ])];
         let border_style = if is_title_focused {
             Style::default()
@@ -417,8 +618,7 @@ fn draw_task_detail(f: &mut Frame, app: &mut App) {
     }
 
     // tags section - show numbered tags for easy removal
-    let is_tags_focused =
-        app.focused_field == TaskField::Tags && !is_editing_title && !is_editing_description;
+    let is_tags_focused = app.focused_field == TaskField::Tags && !is_editing;
 
     let tags_lines = if !task.tags.is_empty() {
         let mut lines = vec![Line::from(vec![
@@ -470,9 +670,82 @@ fn draw_task_detail(f: &mut Frame, app: &mut App) {
     );
     f.render_widget(tags_para, sections[1]);
 
+    // due date section - show input if editing
+    if is_editing_due_date {
+        let due_para = Paragraph::new(app.input_buffer.as_str())
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Due [EDITING] (YYYY-MM-DD, today, tomorrow, fri, +3d)")
+                    .border_style(Style::default().fg(app.theme.accent)),
+            )
+            .style(Style::default().bg(app.theme.background_selected));
+        f.render_widget(due_para, sections[2]);
+    } else {
+        let is_due_focused = app.focused_field == TaskField::DueDate && !is_editing;
+        let due_spans = match task.due {
+            Some(due) => {
+                let days_left = (due - dates::today()).num_days();
+                let (relative, color) = match task.due_status(dates::today()) {
+                    DueStatus::Overdue => (
+                        format!(" (overdue by {})", format_days(-days_left)),
+                        app.theme.danger,
+                    ),
+                    DueStatus::DueSoon if days_left == 0 => {
+                        (" (due today)".to_string(), app.theme.accent)
+                    }
+                    DueStatus::DueSoon => (
+                        format!(" (in {})", format_days(days_left)),
+                        app.theme.accent,
+                    ),
+                    _ => (
+                        format!(" (in {})", format_days(days_left)),
+                        app.theme.text_secondary,
+                    ),
+                };
+                vec![
+                    Span::raw(due.format("%Y-%m-%d").to_string()),
+                    Span::styled(relative, Style::default().fg(color)),
+                ]
+            }
+            None => vec![Span::styled(
+                "No due date (press Enter to set)",
+                Style::default().fg(app.theme.text_secondary),
+            )],
+        };
+        let mut spans = vec![Span::styled(
+            "Due: ",
+            Style::default()
+                .fg(app.theme.primary)
+                .add_modifier(Modifier::BOLD),
+        )];
+        spans.extend(due_spans);
+        let border_style = if is_due_focused {
+            Style::default()
+                .fg(app.theme.accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let due_para = Paragraph::new(Line::from(spans)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(border_style),
+        );
+        f.render_widget(due_para, sections[2]);
+    }
+
+    // dates section - read only
+    let dates_para = Paragraph::new(task_date_lines(app, task)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("Dates")
+            .border_style(Style::default()),
+    );
+    f.render_widget(dates_para, sections[3]);
+
     // description section - show input field if editing, otherwise show text
-    let is_desc_focused =
-        app.focused_field == TaskField::Description && !is_editing_title && !is_editing_description;
+    let is_desc_focused = app.focused_field == TaskField::Description && !is_editing;
 
     if is_editing_description {
         // Show editable input field
@@ -485,7 +758,7 @@ fn draw_task_detail(f: &mut Frame, app: &mut App) {
             )
             .wrap(Wrap { trim: false })
             .style(Style::default().bg(app.theme.background_selected));
-        f.render_widget(desc_para, sections[2]);
+        f.render_widget(desc_para, sections[4]);
     } else {
         // Show read-only description
         let desc_text = if task.description.is_empty() {
@@ -508,7 +781,285 @@ fn draw_task_detail(f: &mut Frame, app: &mut App) {
                     .border_style(border_style),
             )
             .wrap(Wrap { trim: false });
-        f.render_widget(desc_para, sections[2]);
+        f.render_widget(desc_para, sections[4]);
+    }
+
+    // checklist section
+    let is_checklist_focused = app.focused_field == TaskField::Checklist && !is_editing;
+    let (done, total) = task.checklist_progress();
+    let mut checklist_lines: Vec<Line> = Vec::new();
+    if task.checklist.is_empty() && !is_adding_checklist_item {
+        checklist_lines.push(Line::from(Span::styled(
+            "No checklist items (press a to add)",
+            Style::default().fg(app.theme.text_secondary),
+        )));
+    }
+
+    // keep the selected item visible when there are more than 10
+    let first_visible = app.selected_checklist_index.saturating_sub(9);
+    for (i, item) in task
+        .checklist
+        .iter()
+        .enumerate()
+        .skip(first_visible)
+        .take(10)
+    {
+        let is_selected = is_checklist_focused && i == app.selected_checklist_index;
+        let (checkbox, text_style) = if item.done {
+            (
+                "[x] ",
+                Style::default()
+                    .fg(app.theme.text_secondary)
+                    .add_modifier(Modifier::CROSSED_OUT),
+            )
+        } else {
+            ("[ ] ", Style::default().fg(app.theme.text_primary))
+        };
+        let text_style = if is_selected {
+            text_style
+                .bg(app.theme.background_selected)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            text_style
+        };
+        checklist_lines.push(Line::from(vec![
+            Span::styled(
+                if is_selected { "> " } else { "  " },
+                Style::default().fg(app.theme.accent),
+            ),
+            Span::styled(checkbox, Style::default().fg(app.theme.success)),
+            Span::styled(item.text.as_str(), text_style),
+        ]));
+    }
+
+    if is_adding_checklist_item {
+        checklist_lines.push(Line::from(vec![
+            Span::styled("  [ ] ", Style::default().fg(app.theme.accent)),
+            Span::styled(
+                app.input_buffer.as_str(),
+                Style::default().bg(app.theme.background_selected),
+            ),
+        ]));
+    }
+
+    let checklist_title = if is_checklist_focused {
+        format!(
+            "Checklist {}/{} (a: add | Space/Enter: toggle | Shift+J/K: move | d: delete | Tab: next field)",
+            done, total
+        )
+    } else {
+        format!("Checklist {}/{}", done, total)
+    };
+    let border_style = if is_checklist_focused || is_adding_checklist_item {
+        Style::default()
+            .fg(app.theme.accent)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+    let checklist_para = Paragraph::new(checklist_lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(checklist_title)
+            .border_style(border_style),
+    );
+    f.render_widget(checklist_para, sections[5]);
+
+    // dependencies section
+    let is_dependencies_focused = app.focused_field == TaskField::Dependencies && !is_editing;
+    let mut dependency_lines: Vec<Line> = Vec::new();
+    if links.is_empty() && !is_adding_blocker {
+        dependency_lines.push(Line::from(Span::styled(
+            "No dependencies (press a to add a blocker)",
+            Style::default().fg(app.theme.text_secondary),
+        )));
+    }
+    let board = app.board();
+    let first_visible = app.selected_link_index.saturating_sub(5);
+    for (i, (kind, id)) in links.iter().enumerate().skip(first_visible).take(6) {
+        let Some(linked) = board.get_task(id) else {
+            continue;
+        };
+        let is_selected = is_dependencies_focused && i == app.selected_link_index;
+        let is_done = board.is_task_done(id);
+        let label = match kind {
+            LinkKind::BlockedBy => "blocked by ",
+            LinkKind::Blocks => "blocks     ",
+        };
+        let label_color = match kind {
+            LinkKind::BlockedBy if !is_done => app.theme.danger,
+            _ => app.theme.text_secondary,
+        };
+        let column_name = board
+            .find_task(id)
+            .and_then(|(col_idx, _)| board.get_column(col_idx))
+            .map_or("", |col| col.name.as_str());
+        let title_style = if is_selected {
+            Style::default()
+                .fg(app.theme.text_primary)
+                .bg(app.theme.background_selected)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(app.theme.text_primary)
+        };
+        dependency_lines.push(Line::from(vec![
+            Span::styled(
+                if is_selected { "> " } else { "  " },
+                Style::default().fg(app.theme.accent),
+            ),
+            Span::styled(label, Style::default().fg(label_color)),
+            Span::styled(linked.title.as_str(), title_style),
+            Span::styled(
+                format!("  ({}{})", column_name, if is_done { ", done" } else { "" }),
+                Style::default().fg(app.theme.text_secondary),
+            ),
+        ]));
+    }
+
+    if is_adding_blocker {
+        dependency_lines.push(Line::from(vec![
+            Span::styled("  blocked by ", Style::default().fg(app.theme.accent)),
+            Span::styled(
+                app.input_buffer.as_str(),
+                Style::default().bg(app.theme.background_selected),
+            ),
+        ]));
+    }
+
+    let dependencies_title = if is_dependencies_focused {
+        "Dependencies (a: add blocker | Enter: go to task | d: remove | Tab: next field)"
+    } else {
+        "Dependencies"
+    };
+    let border_style = if is_dependencies_focused || is_adding_blocker {
+        Style::default()
+            .fg(app.theme.accent)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+    let dependencies_para = Paragraph::new(dependency_lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(dependencies_title)
+            .border_style(border_style),
+    );
+    f.render_widget(dependencies_para, sections[6]);
+}
+
+// build the lines for the dates section of the task detail view
+fn task_date_lines<'a>(app: &App, task: &Task) -> Vec<Line<'a>> {
+    let label = |text: &'static str| {
+        Span::styled(
+            text,
+            Style::default()
+                .fg(app.theme.primary)
+                .add_modifier(Modifier::BOLD),
+        )
+    };
+    let muted = |text: String| Span::styled(text, Style::default().fg(app.theme.text_secondary));
+    let column_name = |column_id: &str| {
+        app.board()
+            .columns
+            .iter()
+            .find(|col| col.id == column_id)
+            .map_or(column_id.to_string(), |col| col.name.clone())
+    };
+    let now = Utc::now();
+
+    let mut lines = vec![Line::from(vec![
+        label("Created: "),
+        Span::raw(format_timestamp(task.created_at)),
+        label("   Updated: "),
+        Span::raw(format_timestamp(task.updated_at)),
+    ])];
+
+    // time in the current column
+    if let (Some(entry), Some(entered_at)) =
+        (task.column_history.last(), task.entered_current_column())
+    {
+        lines.push(Line::from(vec![
+            label("In column: "),
+            Span::raw(format!(
+                "{} since {} ",
+                column_name(&entry.column_id),
+                format_timestamp(Some(entered_at))
+            )),
+            muted(format!("({})", format_duration(now - entered_at))),
+        ]));
+    }
+
+    match task.completed_at {
+        Some(completed_at) => {
+            let mut spans = vec![
+                label("Completed: "),
+                Span::styled(
+                    format_timestamp(Some(completed_at)),
+                    Style::default().fg(app.theme.success),
+                ),
+            ];
+            if let Some(cycle_time) = task.cycle_time() {
+                spans.push(muted(format!(
+                    " (cycle time {})",
+                    format_duration(cycle_time)
+                )));
+            }
+            lines.push(Line::from(spans));
+        }
+        None => lines.push(Line::from(vec![
+            label("Completed: "),
+            muted("not yet".to_string()),
+        ])),
+    }
+
+    // column history, oldest first
+    if !task.column_history.is_empty() {
+        let history = task
+            .column_history
+            .iter()
+            .map(|entry| {
+                format!(
+                    "{} {}",
+                    column_name(&entry.column_id),
+                    entry.entered_at.with_timezone(&Local).format("%m-%d")
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" → ");
+        lines.push(Line::from(vec![label("History: "), muted(history)]));
+    }
+
+    lines
+}
+
+// format a timestamp in local time, or "unknown" for tasks created before tracking
+fn format_timestamp(timestamp: Option<DateTime<Utc>>) -> String {
+    timestamp.map_or("unknown".to_string(), |ts| {
+        ts.with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    })
+}
+
+// "1 day" / "3 days"
+fn format_days(days: i64) -> String {
+    if days == 1 {
+        "1 day".to_string()
+    } else {
+        format!("{} days", days)
+    }
+}
+
+// format a duration compactly, e.g. "3d 4h", "2h 5m" or "12m"
+fn format_duration(duration: chrono::Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    let (days, hours, mins) = (minutes / 1440, (minutes % 1440) / 60, minutes % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else {
+        format!("{}m", mins)
     }
 }
 
@@ -548,6 +1099,9 @@ fn draw_help(f: &mut Frame, app: &mut App) {
         Line::from("  Shift+D : Delete current column (if empty)"),
         Line::from("  Shift+H/← : Move column left"),
         Line::from("  Shift+L/→ : Move column right"),
+        Line::from("  Shift+S : Toggle sort by priority/due date for current column"),
+        Line::from("  Shift+G : Toggle current column as the gate blocked tasks can't pass"),
+        Line::from("  Shift+W : Set WIP limit for current column (empty to remove)"),
         Line::from(""),
         Line::from(vec![Span::styled(
             "Task Management:",
@@ -560,8 +1114,16 @@ fn draw_help(f: &mut Frame, app: &mut App) {
         Line::from("  t     : Add tag to selected task"),
         Line::from("  m     : Move task forward (to next column)"),
         Line::from("  n     : Move task backward (to previous column)"),
+        Line::from("  Shift+J/K : Move task down/up within its column"),
+        Line::from("  i     : Cycle where moved tasks land (bottom, top, same row)"),
         Line::from("  d     : Delete selected task"),
+        Line::from("  +/-   : Raise/lower priority of selected task"),
+        Line::from("  u     : Undo last change"),
+        Line::from("  Ctrl+r : Redo last undone change"),
+        Line::from("  Ctrl+s : Save now (re-enables saving after a load error)"),
+        Line::from("  ⊘     : Task is blocked by unfinished tasks (see Dependencies in details)"),
         Line::from("  e     : Edit description (when viewing task)"),
+        Line::from("  Due   : Set in task details (2026-11-03, tomorrow, fri, +3d)"),
         Line::from(""),
         Line::from(vec![Span::styled(
             "Predefined Tags:",
@@ -812,6 +1374,14 @@ fn draw_project_list(f: &mut Frame, app: &mut App) {
 
             spans.push(Span::styled(&project.name, style));
 
+            // Repository board indicator
+            if app.is_repo_project(i) {
+                spans.push(Span::styled(
+                    " [repo]",
+                    Style::default().fg(app.theme.secondary),
+                ));
+            }
+
             // Current indicator
             if is_current {
                 spans.push(Span::styled(
@@ -936,11 +1506,162 @@ fn draw_delete_confirmation(f: &mut Frame, app: &mut App) {
     f.render_widget(para, dialog_area);
 }
 
+// draw a popup asking whether to take the changes on disk or keep ours
+fn draw_reload_conflict(f: &mut Frame, app: &App) {
+    let area = f.area();
+    let dialog_width = 64.min(area.width.saturating_sub(4));
+    let dialog_height = 10;
+    let dialog_x = (area.width.saturating_sub(dialog_width)) / 2;
+    let dialog_y = (area.height.saturating_sub(dialog_height)) / 2;
+
+    let dialog_area = Rect {
+        x: dialog_x,
+        y: dialog_y,
+        width: dialog_width,
+        height: dialog_height,
+    };
+
+    let key_style = Style::default()
+        .fg(app.theme.accent)
+        .add_modifier(Modifier::BOLD);
+    let text_style = Style::default().fg(app.theme.text_secondary);
+    let message = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            "The projects were changed by another program,",
+            Style::default().fg(app.theme.text_primary),
+        )),
+        Line::from(Span::styled(
+            "but your latest changes haven't been saved.",
+            Style::default().fg(app.theme.danger),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("r", key_style),
+            Span::styled(": reload and discard your changes", text_style),
+        ]),
+        Line::from(vec![
+            Span::styled("k", key_style),
+            Span::styled(": keep your changes and overwrite the file", text_style),
+        ]),
+        Line::from(vec![
+            Span::styled("Esc", key_style),
+            Span::styled(": decide later, your changes stay unsaved", text_style),
+        ]),
+    ];
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.danger))
+        .title(" File Changed on Disk ")
+        .style(Style::default().bg(Color::Black));
+
+    let para = Paragraph::new(message)
+        .block(block)
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(Clear, dialog_area);
+    f.render_widget(para, dialog_area);
+}
+
+// draw a popup asking to confirm a move over a column's WIP limit
+fn draw_wip_limit_confirmation(f: &mut Frame, app: &App) {
+    let Some(target_column) = app.pending_move.and_then(|idx| app.board().get_column(idx)) else {
+        return;
+    };
+    let limit = target_column.wip_limit.unwrap_or(0);
+
+    let area = f.area();
+    let dialog_width = 60.min(area.width - 4);
+    let dialog_height = 8;
+    let dialog_x = (area.width.saturating_sub(dialog_width)) / 2;
+    let dialog_y = (area.height.saturating_sub(dialog_height)) / 2;
+
+    let dialog_area = Rect {
+        x: dialog_x,
+        y: dialog_y,
+        width: dialog_width,
+        height: dialog_height,
+    };
+
+    let message = vec![
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                format!("'{}'", target_column.name),
+                Style::default()
+                    .fg(app.theme.accent)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(
+                    " already has {} of {} tasks.",
+                    target_column.tasks.len(),
+                    limit
+                ),
+                Style::default().fg(app.theme.text_primary),
+            ),
+        ]),
+        Line::from(Span::styled(
+            "Moving this task will exceed its WIP limit.",
+            Style::default().fg(app.theme.danger),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("Press ", Style::default().fg(app.theme.text_secondary)),
+            Span::styled(
+                "y",
+                Style::default()
+                    .fg(app.theme.success)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                " to move anyway, ",
+                Style::default().fg(app.theme.text_secondary),
+            ),
+            Span::styled(
+                "n",
+                Style::default()
+                    .fg(app.theme.danger)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" or ", Style::default().fg(app.theme.text_secondary)),
+            Span::styled(
+                "Esc",
+                Style::default()
+                    .fg(app.theme.danger)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(" to cancel", Style::default().fg(app.theme.text_secondary)),
+        ]),
+    ];
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.danger))
+        .title(" WIP Limit ")
+        .style(Style::default().bg(Color::Black));
+
+    let para = Paragraph::new(message)
+        .block(block)
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(Clear, dialog_area);
+    f.render_widget(para, dialog_area);
+}
+
 // draw a popup showing error info
 fn draw_error_popup(f: &mut Frame, app: &mut App) {
     let area = f.area();
-    let dialog_width = 70.min(area.width - 4);
-    let dialog_height = 10;
+    let dialog_width = 70.min(area.width.saturating_sub(4));
+    // grow to fit long messages, e.g. a corrupt project file
+    let text_width = dialog_width.saturating_sub(2).max(1) as usize;
+    let message_height: usize = app
+        .error_message
+        .lines()
+        .map(|line| line.chars().count().div_ceil(text_width).max(1))
+        .sum();
+    let dialog_height = ((message_height + 7) as u16).clamp(10, area.height);
     let dialog_x = (area.width.saturating_sub(dialog_width)) / 2;
     let dialog_y = (area.height.saturating_sub(dialog_height)) / 2;
 
@@ -957,7 +1678,7 @@ fn draw_error_popup(f: &mut Frame, app: &mut App) {
         .title(" Error ")
         .style(Style::default().bg(Color::Black));
 
-    let message = vec![
+    let mut message = vec![
         Line::from(""),
         Line::from(vec![Span::styled(
             "An error occurred:",
@@ -966,13 +1687,19 @@ fn draw_error_popup(f: &mut Frame, app: &mut App) {
                 .add_modifier(Modifier::BOLD),
         )]),
         Line::from(""),
-        Line::from(Span::raw(&app.error_message)),
+    ];
+    message.extend(
+        app.error_message
+            .lines()
+            .map(|line| Line::from(Span::raw(line))),
+    );
+    message.extend([
         Line::from(""),
         Line::from(vec![Span::styled(
             "Press Esc or Enter to close this message.",
             Style::default().fg(app.theme.text_secondary),
         )]),
-    ];
+    ]);
 
     let para = Paragraph::new(message)
         .block(block)